@@ -0,0 +1,245 @@
+//! Card data from the api's bulk data export
+
+use crate::codec::{Card, Deck, Language};
+use crate::normalize::copy_key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::{fmt, fs, path::Path};
+
+/// Card data shared by every copy of a card number and parallel id in a
+/// language
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CardData {
+    /// card name
+    pub name: String,
+    /// "digimon", "digi-egg", "tamer" or "option"
+    pub category: String,
+    #[serde(default, rename = "color", skip_serializing_if = "Vec::is_empty")]
+    /// card colors in card order (i.e. "red")
+    pub colors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// digimon and digi-egg level
+    pub level: Option<u8>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    /// digimon type (i.e. "Dragon Kin")
+    pub card_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// digimon and tamer play cost
+    pub play_cost: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// option use cost
+    pub use_cost: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// rarity (i.e. "SR")
+    pub rarity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// digimon form (i.e. "Rookie")
+    pub form: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// digimon attribute (i.e. "Vaccine")
+    pub attribute: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// cards a digimon can digivolve from
+    pub digivolution_requirements: Vec<DigivolutionRequirement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// security effect text
+    pub security_effect: Option<String>,
+}
+
+/// Card a digimon can digivolve from and the cost to digivolve
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DigivolutionRequirement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// level of the card
+    pub level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// category of the card
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// form of the card
+    pub form: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// colors the card may have
+    pub color: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// digivolve cost
+    pub cost: Option<u8>,
+}
+
+/// Card attributes of a bulk data card
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BulkCardAttributes {
+    number: String,
+    #[serde(default)]
+    parallel_id: Option<u8>,
+    language: Language,
+    #[serde(flatten)]
+    data: CardData,
+}
+
+/// Card resource of a bulk data card
+#[derive(Deserialize)]
+struct BulkCardResource {
+    attributes: BulkCardAttributes,
+}
+
+/// A bulk data card document
+#[derive(Deserialize)]
+struct BulkCard {
+    data: BulkCardResource,
+}
+
+/// Error returned when a card database can't be loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadCardDbError {
+    /// file could not be read
+    Io(String),
+    /// file is not a bulk data card export
+    Parse(String),
+}
+
+impl fmt::Display for LoadCardDbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadCardDbError::Io(message) => {
+                write!(f, "Failed to read card database: {}", message)
+            }
+            LoadCardDbError::Parse(message) => {
+                write!(f, "Invalid card database: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadCardDbError {}
+
+/// Cards of the api's bulk data export by card number, parallel id and
+/// language
+///
+/// Card numbers are looked up regardless of case and zero padding.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CardDb {
+    cards: HashMap<(String, u8), HashMap<String, CardData>>,
+}
+
+impl CardDb {
+    /// Load a bulk data export (i.e. "all_cards-2024-01-01-000000.json")
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<CardDb, LoadCardDbError> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| LoadCardDbError::Io(e.to_string()))?;
+        CardDb::from_json(&json)
+    }
+
+    /// Read a bulk data export, a JSON array of card documents
+    pub fn from_json(json: &str) -> Result<CardDb, LoadCardDbError> {
+        let bulk_cards: Vec<BulkCard> = serde_json::from_str(json)
+            .map_err(|e| LoadCardDbError::Parse(e.to_string()))?;
+        let mut card_db = CardDb::default();
+        for BulkCard {
+            data: BulkCardResource { attributes },
+        } in bulk_cards
+        {
+            card_db
+                .cards
+                .entry((
+                    copy_key(&attributes.number),
+                    attributes.parallel_id.unwrap_or(0),
+                ))
+                .or_default()
+                .insert(attributes.language.to_string(), attributes.data);
+        }
+        Ok(card_db)
+    }
+
+    /// Count of cards, counting every parallel id and language
+    pub fn len(&self) -> usize {
+        self.cards.values().map(HashMap::len).sum()
+    }
+
+    /// Whether there are no cards
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Card data of a card number and parallel id in a language
+    pub fn get(
+        &self,
+        number: &str,
+        parallel_id: u8,
+        language: &Language,
+    ) -> Option<&CardData> {
+        self.cards
+            .get(&(copy_key(number), parallel_id))?
+            .get(&language.to_string())
+    }
+
+    /// Card data of a card in a language, using the card data of the
+    /// original art for alternate arts missing from the database
+    pub fn card(&self, card: &Card, language: &Language) -> Option<&CardData> {
+        self.get(&card.number, card.parallel_id, language)
+            .or_else(|| self.get(&card.number, 0, language))
+    }
+}
+
+/// A card with its card data, if the card database has it
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EnrichedCard {
+    #[serde(flatten)]
+    /// card number, parallel id and count
+    pub card: Card,
+    #[serde(flatten)]
+    /// card data in the deck language
+    pub data: Option<CardData>,
+}
+
+/// A deck with the card data of its cards
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EnrichedDeck {
+    #[serde(rename = "digi-eggs")]
+    /// cards in digi-egg deck
+    pub digi_eggs: Vec<EnrichedCard>,
+    /// cards in main deck
+    pub deck: Vec<EnrichedCard>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// cards in sideboard
+    pub sideboard: Vec<EnrichedCard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// deck icon
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// deck language
+    pub language: Option<Language>,
+    /// deck name
+    pub name: String,
+}
+
+impl Deck {
+    /// Add the card data of every card from `card_db`, in the deck language
+    /// or `language` for decks without one
+    pub fn enrich(
+        &self,
+        card_db: &CardDb,
+        language: &Language,
+    ) -> EnrichedDeck {
+        let language = self.language.as_ref().unwrap_or(language);
+        let enrich_cards = |cards: &[Card]| {
+            cards
+                .iter()
+                .map(|card| EnrichedCard {
+                    card: card.clone(),
+                    data: card_db.card(card, language).cloned(),
+                })
+                .collect()
+        };
+        EnrichedDeck {
+            digi_eggs: enrich_cards(&self.digi_eggs),
+            deck: enrich_cards(&self.deck),
+            sideboard: enrich_cards(&self.sideboard),
+            icon: self.icon.clone(),
+            language: self.language.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
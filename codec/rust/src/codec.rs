@@ -1,14 +1,32 @@
 //! Codec constants and structs
+pub mod card_number;
 pub mod decode;
 pub mod encode;
+pub mod transcode;
 
-pub use crate::codec::decode::decode;
-pub use crate::codec::encode::encode;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+pub use crate::codec::card_number::{CardNumber, ParseCardNumberError};
+pub use crate::codec::decode::{
+    decode, inspect, try_decode, try_decode_verified, DecodeError,
+};
+pub use crate::codec::encode::{
+    encode, encode_bytes_into, encode_canonical, encode_into, encode_minimal,
+    loss_warnings, minimal_version, try_encode, EncodeError, Encoder,
+    LossWarning,
+};
+pub use crate::codec::transcode::{transcode, TranscodeError};
+use crate::normalize::default_padding_rules;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, str::FromStr};
 
-/// Version of the codec
-pub const VERSION: u8 = 5;
+/// Version of the codec used by default to encode deck codes
+pub const VERSION: u8 = 7;
+
+/// Newest version of the codec that can be encoded and decoded
+///
+/// v8 stores the deck language in its own byte, keeping decks without a
+/// language and unknown language numbers as they are. Other
+/// implementations can't read it yet, so it is only encoded on request.
+pub const MAX_VERSION: u8 = 8;
 
 /// Deck codes are all prefixed with "DCG"
 pub const PREFIX: &str = "DCG";
@@ -17,7 +35,7 @@ pub const PREFIX: &str = "DCG";
 pub const HEADER_SIZE: usize = 3;
 
 fn is_zero(n: &u8) -> bool {
-    return *n == 0;
+    *n == 0
 }
 
 /// A deck's digi-eggs and main deck is made of Card structs
@@ -37,21 +55,123 @@ pub struct Card {
     pub count: u8,
 }
 
+impl Card {
+    /// Parse the card number identifier
+    pub fn card_number(&self) -> Result<CardNumber, ParseCardNumberError> {
+        self.number.parse()
+    }
+}
+
 /// Deck language
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Language {
     /// Japanese
-    #[serde(alias = "ja", rename(serialize = "ja"))]
     Japanese,
     /// English
-    #[serde(alias = "en", rename(serialize = "en"))]
     English,
     /// Chinese
-    #[serde(alias = "zh-Hans", rename(serialize = "zh-Hans"))]
     Chinese,
     /// Korean
-    #[serde(alias = "ko", rename(serialize = "ko"))]
     Korean,
+    /// language number without a known language, kept so the deck code can
+    /// be re-encoded as it was
+    Unknown(u8),
+}
+
+/// Error returned when a string is not a language tag of a known language
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLanguageError {
+    /// language tag
+    pub tag: String,
+}
+
+impl fmt::Display for ParseLanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid language tag '{}'", self.tag)
+    }
+}
+
+impl std::error::Error for ParseLanguageError {}
+
+impl Language {
+    /// Language of a language number stored in a deck code
+    pub const fn from_number(number: u8) -> Self {
+        match number {
+            0 => Language::Japanese,
+            1 => Language::English,
+            2 => Language::Chinese,
+            3 => Language::Korean,
+            _ => Language::Unknown(number),
+        }
+    }
+
+    /// Language number stored in a deck code
+    pub const fn number(&self) -> u8 {
+        match self {
+            Language::Japanese => 0,
+            Language::English => 1,
+            Language::Chinese => 2,
+            Language::Korean => 3,
+            Language::Unknown(number) => *number,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = ParseLanguageError;
+
+    /// Parse a BCP-47 language tag, where unknown language numbers use the
+    /// private use tag "x-<number>"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.to_ascii_lowercase();
+        match tag.as_str() {
+            "ja" => Ok(Language::Japanese),
+            "en" => Ok(Language::English),
+            "zh" | "zh-hans" => Ok(Language::Chinese),
+            "ko" => Ok(Language::Korean),
+            _ => tag
+                .strip_prefix("x-")
+                .and_then(|number| number.parse().ok())
+                .map(Language::from_number)
+                .ok_or_else(|| ParseLanguageError { tag: s.to_string() }),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Japanese => write!(f, "ja"),
+            Language::English => write!(f, "en"),
+            Language::Chinese => write!(f, "zh-Hans"),
+            Language::Korean => write!(f, "ko"),
+            Language::Unknown(number) => write!(f, "x-{}", number),
+        }
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let tag = String::deserialize(deserializer)?;
+        match tag.as_str() {
+            "Japanese" => Ok(Language::Japanese),
+            "English" => Ok(Language::English),
+            "Chinese" => Ok(Language::Chinese),
+            "Korean" => Ok(Language::Korean),
+            _ => tag.parse().map_err(de::Error::custom),
+        }
+    }
 }
 
 /// A deck has digi-egg cards (0-5 Cards), a main deck of cards (50 Cards), and a name (0-63 bytes)
@@ -75,6 +195,104 @@ pub struct Deck {
     pub name: String,
 }
 
+impl Deck {
+    /// Normalize every card number by the paddings of the released card
+    /// sets, merge Card entries with the same card number and parallel id by
+    /// summing their counts, and sort each section the way the encoder does
+    ///
+    /// Card numbers are normalized the same way regardless of the rest of
+    /// the deck. Summed counts above 255 are kept in further entries of the
+    /// same card.
+    pub fn canonicalize(&mut self) {
+        let padding_rules = default_padding_rules();
+        for cards in [&mut self.digi_eggs, &mut self.deck, &mut self.sideboard]
+        {
+            for card in cards.iter_mut() {
+                if let Ok(card_number) = padding_rules.normalize(&card.number) {
+                    card.number = card_number.to_string();
+                }
+            }
+            cards.sort_by(card_order);
+            let mut merged_cards: Vec<Card> = Vec::with_capacity(cards.len());
+            for card in cards.drain(..) {
+                match merged_cards.last_mut() {
+                    Some(prev_card)
+                        if prev_card.number == card.number
+                            && prev_card.parallel_id == card.parallel_id =>
+                    {
+                        let count =
+                            u16::from(prev_card.count) + u16::from(card.count);
+                        prev_card.count = count.min(u8::MAX.into()) as u8;
+                        if count > u8::MAX.into() {
+                            merged_cards.push(Card {
+                                count: (count - u16::from(u8::MAX)) as u8,
+                                ..card
+                            });
+                        }
+                    }
+                    _ => merged_cards.push(card),
+                }
+            }
+            *cards = merged_cards;
+        }
+    }
+}
+
+/// Order cards are stored in a deck code
+pub fn card_order(a: &Card, b: &Card) -> Ordering {
+    (a.number.as_str(), a.parallel_id).cmp(&(b.number.as_str(), b.parallel_id))
+}
+
+/// Deck code metadata that can be read without parsing the cards
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeckHeader {
+    /// codec version of the deck code
+    pub version: u8,
+    /// checksum as stored in the deck code
+    pub checksum: u16,
+    #[serde(rename(serialize = "checksum-algorithm"))]
+    /// algorithm the checksum was computed with
+    pub checksum_algorithm: ChecksumAlgorithm,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// deck language
+    pub language: Option<Language>,
+    #[serde(rename(serialize = "digi-egg-count"))]
+    /// count of Card entries in digi-egg deck
+    pub digi_egg_count: usize,
+    #[serde(rename(serialize = "sideboard-count"))]
+    /// count of Card entries in sideboard
+    pub sideboard_count: usize,
+    #[serde(rename(serialize = "has-icon"))]
+    /// whether the deck code stores an icon
+    pub has_icon: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// deck icon
+    pub icon: Option<String>,
+    /// deck name
+    pub name: String,
+}
+
+/// Checksum algorithm used by a deck code version
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChecksumAlgorithm {
+    /// sum of card bytes modulo 256 (v0-v6)
+    Additive,
+    /// CRC-16/CCITT-FALSE over all bytes but the checksum (v7)
+    Crc16,
+}
+
+impl ChecksumAlgorithm {
+    /// Checksum algorithm of a deck code version
+    pub const fn for_version(version: u8) -> Self {
+        if version >= 7 {
+            ChecksumAlgorithm::Crc16
+        } else {
+            ChecksumAlgorithm::Additive
+        }
+    }
+}
+
 /// Compute checksum of deck that excludes header and deck name
 pub fn compute_checksum(total_card_bytes: usize, deck_bytes: &[u8]) -> u8 {
     let checksum = deck_bytes[..total_card_bytes]
@@ -84,90 +302,61 @@ pub fn compute_checksum(total_card_bytes: usize, deck_bytes: &[u8]) -> u8 {
     (checksum & 0xFF) as u8
 }
 
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+/// Compute CRC-16/CCITT-FALSE of deck bytes that excludes the two checksum
+/// bytes of a v7 header
+pub fn compute_crc16(deck_bytes: &[u8]) -> u16 {
+    // checksum is stored in the second and third bytes
+    deck_bytes[..1].iter().chain(&deck_bytes[3..]).fold(
+        0xFFFF,
+        |crc: u16, &b| {
+            crc << 8 ^ CRC16_TABLE[usize::from((crc >> 8) as u8 ^ b)]
+        },
+    )
+}
+
+const BASE36_CHARS: [&str; 36] = [
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E",
+    "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T",
+    "U", "V", "W", "X", "Y", "Z",
+];
+
 /// Convert a u8 to a base36 character
-pub fn base36_to_char(base36: u8) -> &'static str {
-    let lookup: HashMap<u8, &str> = HashMap::from([
-        (0, "0"),
-        (1, "1"),
-        (2, "2"),
-        (3, "3"),
-        (4, "4"),
-        (5, "5"),
-        (6, "6"),
-        (7, "7"),
-        (8, "8"),
-        (9, "9"),
-        (10, "A"),
-        (11, "B"),
-        (12, "C"),
-        (13, "D"),
-        (14, "E"),
-        (15, "F"),
-        (16, "G"),
-        (17, "H"),
-        (18, "I"),
-        (19, "J"),
-        (20, "K"),
-        (21, "L"),
-        (22, "M"),
-        (23, "N"),
-        (24, "O"),
-        (25, "P"),
-        (26, "Q"),
-        (27, "R"),
-        (28, "S"),
-        (29, "T"),
-        (30, "U"),
-        (31, "V"),
-        (32, "W"),
-        (33, "X"),
-        (34, "Y"),
-        (35, "Z"),
-    ]);
-    lookup.get(&base36).unwrap_or(&"")
+pub const fn base36_to_char(base36: u8) -> &'static str {
+    if (base36 as usize) < BASE36_CHARS.len() {
+        BASE36_CHARS[base36 as usize]
+    } else {
+        ""
+    }
 }
 
 /// Convert a base36 character to a u8
-pub fn char_to_base36(chr: &char) -> u8 {
-    let chr_string = chr.to_string().to_uppercase();
-    let chr = chr_string.as_str();
-    let lookup: HashMap<&str, u8> = HashMap::from([
-        ("0", 0),
-        ("1", 1),
-        ("2", 2),
-        ("3", 3),
-        ("4", 4),
-        ("5", 5),
-        ("6", 6),
-        ("7", 7),
-        ("8", 8),
-        ("9", 9),
-        ("A", 10),
-        ("B", 11),
-        ("C", 12),
-        ("D", 13),
-        ("E", 14),
-        ("F", 15),
-        ("G", 16),
-        ("H", 17),
-        ("I", 18),
-        ("J", 19),
-        ("K", 20),
-        ("L", 21),
-        ("M", 22),
-        ("N", 23),
-        ("O", 24),
-        ("P", 25),
-        ("Q", 26),
-        ("R", 27),
-        ("S", 28),
-        ("T", 29),
-        ("U", 30),
-        ("V", 31),
-        ("W", 32),
-        ("X", 33),
-        ("Y", 34),
-        ("Z", 35),
-    ]);
-    *lookup.get(&chr).unwrap_or(&0)
+pub const fn char_to_base36(chr: &char) -> u8 {
+    match *chr {
+        '0'..='9' => *chr as u8 - b'0',
+        'A'..='Z' => *chr as u8 - b'A' + 10,
+        'a'..='z' => *chr as u8 - b'a' + 10,
+        _ => 0,
+    }
 }
@@ -0,0 +1,125 @@
+//! Card numbers
+
+use std::{fmt, str::FromStr};
+
+/// Card number split into its parts (i.e. "BT1-001" or "P-001a")
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CardNumber {
+    /// card set code before the last hyphen (i.e. "BT1")
+    pub card_set: String,
+    /// numeric part after the last hyphen
+    pub number: u32,
+    /// count of digits in the numeric part including zero padding
+    pub padding: usize,
+    /// characters following the numeric part
+    pub suffix: Option<String>,
+}
+
+/// Error returned when a string is not a card set and a number separated by
+/// a hyphen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCardNumberError {
+    /// card number identifier
+    pub number: String,
+}
+
+impl fmt::Display for ParseCardNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid card number '{}'", self.number)
+    }
+}
+
+impl std::error::Error for ParseCardNumberError {}
+
+/// Card number split into parts borrowed from the card number identifier,
+/// so encoding can read every card number without allocating
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CardNumberParts<'a> {
+    /// card set code before the last hyphen
+    pub card_set: &'a str,
+    /// numeric part after the last hyphen
+    pub number: u32,
+    /// count of digits in the numeric part including zero padding
+    pub padding: usize,
+    /// characters following the numeric part, empty without a suffix
+    pub suffix: &'a str,
+}
+
+impl<'a> CardNumberParts<'a> {
+    /// Split a card number identifier, or None when it is not a card set
+    /// and a number separated by a hyphen
+    pub fn parse(s: &'a str) -> Option<Self> {
+        let (card_set, rest) = s.rsplit_once('-')?;
+        let padding = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if card_set.is_empty() || padding == 0 {
+            return None;
+        }
+        let (number, suffix) = rest.split_at(padding);
+        Some(CardNumberParts {
+            card_set,
+            number: number.parse().ok()?,
+            padding,
+            suffix,
+        })
+    }
+
+    /// Whether a deck code of `version` stores this card number without
+    /// changing it
+    pub fn is_encodable(&self, version: u8) -> bool {
+        !self.card_set.is_empty()
+            && self
+                .card_set
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+            && (version != 0 || self.card_set.len() <= 4)
+            && (1..=4).contains(&self.padding)
+            && self.suffix.is_empty()
+    }
+}
+
+impl CardNumber {
+    /// Whether a deck code of `version` stores this card number without
+    /// changing it
+    ///
+    /// Card sets are stored as base36 characters (4 bytes in v0), zero
+    /// padding is stored in 2 bits and suffixes are not stored at all.
+    pub fn is_encodable(&self, version: u8) -> bool {
+        CardNumberParts {
+            card_set: &self.card_set,
+            number: self.number,
+            padding: self.padding,
+            suffix: self.suffix.as_deref().unwrap_or(""),
+        }
+        .is_encodable(version)
+    }
+}
+
+impl FromStr for CardNumber {
+    type Err = ParseCardNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts =
+            CardNumberParts::parse(s).ok_or_else(|| ParseCardNumberError {
+                number: s.to_string(),
+            })?;
+        Ok(CardNumber {
+            card_set: parts.card_set.to_string(),
+            number: parts.number,
+            padding: parts.padding,
+            suffix: Some(parts.suffix.to_string()).filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+impl fmt::Display for CardNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{:0>p$}{}",
+            self.card_set,
+            self.number,
+            self.suffix.as_deref().unwrap_or(""),
+            p = self.padding
+        )
+    }
+}
@@ -1,23 +1,194 @@
 //! Decoder
 
 pub use crate::codec::{
-    base36_to_char, compute_checksum, Card, Deck, Language, PREFIX, VERSION,
+    base36_to_char, compute_checksum, compute_crc16, Card, ChecksumAlgorithm,
+    Deck, DeckHeader, Language, HEADER_SIZE, MAX_VERSION, PREFIX, VERSION,
 };
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use std::{convert::TryFrom, fmt, str};
 
-fn get_u8(deck_bytes: &mut Vec<u8>) -> u8 {
-    *deck_bytes.drain(..1).collect::<Vec<u8>>().first().unwrap()
+/// Errors that can occur while decoding a deck code
+///
+/// `offset` is a character offset into the deck code string for
+/// `BadPrefix` and `BadBase64`, and a byte offset into the base64 decoded
+/// deck bytes for every other variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// deck code does not start with PREFIX
+    BadPrefix {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// deck code is not valid URL-safe base64
+    BadBase64 {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// deck code version is newer than MAX_VERSION
+    UnsupportedVersion {
+        /// offset where parsing failed
+        offset: usize,
+        /// version stored in the deck code
+        version: u8,
+    },
+    /// deck bytes end before the header is complete
+    TruncatedHeader {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// checksum stored in the header does not match the card bytes
+    ChecksumMismatch {
+        /// offset where parsing failed
+        offset: usize,
+        /// checksum stored in the header
+        expected: u16,
+        /// checksum computed from the deck bytes
+        computed: u16,
+    },
+    /// card section ends before all cards could be read
+    TruncatedCards {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// carry bits of an encoded value continue past 32 bits
+    ValueOverflow {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// card set is not valid UTF-8 or base36
+    InvalidCardSet {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// card number has more digits than the zero padding of its card set
+    InvalidCardNumber {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// deck name is not valid UTF-8
+    InvalidName {
+        /// offset where parsing failed
+        offset: usize,
+    },
+    /// deck icon does not fit in the deck name bytes
+    IconOverflow {
+        /// offset where parsing failed
+        offset: usize,
+    },
 }
 
-fn get_u32(deck_bytes: &mut Vec<u8>) -> Vec<u8> {
-    (*deck_bytes.drain(..4).collect::<Vec<u8>>()).to_vec()
+impl DecodeError {
+    /// Offset where parsing failed
+    pub fn offset(&self) -> usize {
+        match *self {
+            DecodeError::BadPrefix { offset }
+            | DecodeError::BadBase64 { offset }
+            | DecodeError::UnsupportedVersion { offset, .. }
+            | DecodeError::TruncatedHeader { offset }
+            | DecodeError::ChecksumMismatch { offset, .. }
+            | DecodeError::TruncatedCards { offset }
+            | DecodeError::ValueOverflow { offset }
+            | DecodeError::InvalidCardSet { offset }
+            | DecodeError::InvalidCardNumber { offset }
+            | DecodeError::InvalidName { offset }
+            | DecodeError::IconOverflow { offset } => offset,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadPrefix { offset } => {
+                write!(f, "Prefix was not '{}' (offset {})", PREFIX, offset)
+            }
+            DecodeError::BadBase64 { offset } => {
+                write!(f, "Invalid base64 (offset {})", offset)
+            }
+            DecodeError::UnsupportedVersion { offset, version } => write!(
+                f,
+                "Deck version {} not supported (offset {})",
+                version, offset
+            ),
+            DecodeError::TruncatedHeader { offset } => {
+                write!(f, "Deck header is truncated (offset {})", offset)
+            }
+            DecodeError::ChecksumMismatch {
+                offset,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "Deck checksum failed: expected {}, computed {} (offset {})",
+                expected, computed, offset
+            ),
+            DecodeError::TruncatedCards { offset } => {
+                write!(f, "Deck cards are truncated (offset {})", offset)
+            }
+            DecodeError::ValueOverflow { offset } => {
+                write!(f, "Encoded value exceeds 32 bits (offset {})", offset)
+            }
+            DecodeError::InvalidCardSet { offset } => {
+                write!(f, "Card set is not valid (offset {})", offset)
+            }
+            DecodeError::InvalidCardNumber { offset } => {
+                write!(
+                    f,
+                    "Card number exceeds its zero padding (offset {})",
+                    offset
+                )
+            }
+            DecodeError::InvalidName { offset } => {
+                write!(f, "Deck name is not valid UTF-8 (offset {})", offset)
+            }
+            DecodeError::IconOverflow { offset } => {
+                write!(f, "Deck icon overflows deck name (offset {})", offset)
+            }
+        }
+    }
 }
 
-fn get_string_from_bytes(card_set_bytes: Vec<u8>) -> String {
-    String::from_utf8(card_set_bytes)
-        .unwrap()
-        .trim()
-        .to_string()
+impl std::error::Error for DecodeError {}
+
+/// Borrowed cursor over deck bytes that tracks the current read position
+struct DeckCursor<'a> {
+    deck_bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DeckCursor<'a> {
+    fn new(deck_bytes: &'a [u8]) -> Self {
+        DeckCursor {
+            deck_bytes,
+            position: 0,
+        }
+    }
+
+    /// Byte offset of the next byte to be read
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Bytes that have not been read yet
+    fn rest(&self) -> &'a [u8] {
+        &self.deck_bytes[self.position..]
+    }
+
+    fn get_u8(&mut self) -> Option<u8> {
+        let byte = *self.deck_bytes.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn get_bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let bytes = self.deck_bytes.get(self.position..self.position + n)?;
+        self.position += n;
+        Some(bytes)
+    }
+}
+
+fn get_str_from_bytes(bytes: &[u8]) -> Option<&str> {
+    str::from_utf8(bytes).ok().map(|s| s.trim())
 }
 
 fn is_carry_bit(current_byte: u8, mask_bits: u8) -> bool {
@@ -36,47 +207,94 @@ fn read_bits_from_byte(
 fn read_encoded_u32(
     mut base_value: u32,
     current_byte: u8,
-    mut delta_shift: u8,
-    mut deck_bytes: &mut Vec<u8>,
-) -> u32 {
-    if delta_shift - 1 == 0 || is_carry_bit(current_byte, &delta_shift - 1) {
-        loop {
-            let next_byte = get_u8(&mut deck_bytes);
-            base_value = read_bits_from_byte(
-                next_byte,
-                u8::BITS as u8 - 1,
-                &delta_shift - 1,
-                base_value,
-            );
-            if !is_carry_bit(next_byte, u8::BITS as u8 - 1) {
-                break;
-            }
-            delta_shift += u8::BITS as u8 - 1;
+    delta_shift: u8,
+    cursor: &mut DeckCursor,
+) -> Result<u32, DecodeError> {
+    if delta_shift - 1 != 0 && !is_carry_bit(current_byte, delta_shift - 1) {
+        return Ok(read_bits_from_byte(current_byte, delta_shift - 1, 0, 0));
+    }
+    let mut shift = u32::from(delta_shift - 1);
+    loop {
+        let offset = cursor.position();
+        let next_byte = cursor
+            .get_u8()
+            .ok_or(DecodeError::TruncatedCards { offset })?;
+        let bits = u32::from(next_byte & 0x7F);
+        // carry bytes past 32 bits or bits shifted out of a u32 would
+        // silently decode to a different value
+        base_value |= bits
+            .checked_shl(shift)
+            .filter(|value| value >> shift == bits)
+            .ok_or(DecodeError::ValueOverflow { offset })?;
+        if !is_carry_bit(next_byte, u8::BITS as u8 - 1) {
+            return Ok(base_value);
         }
-    } else {
-        return read_bits_from_byte(current_byte, &delta_shift - 1, 0, 0);
-    };
-
-    base_value
+        shift += u8::BITS - 1;
+    }
 }
 
 fn deserialize_card(
     version: u8,
-    mut deck_bytes: &mut Vec<u8>,
-    card_set: &String,
+    cursor: &mut DeckCursor,
+    card_set: &str,
     card_set_padding: usize,
     prev_card_number: &mut u32,
-) -> Card {
-    let current_byte: u8 = get_u8(&mut deck_bytes);
+) -> Result<Card, DecodeError> {
+    let card_offset = cursor.position();
+    let truncated_cards = |cursor: &DeckCursor| DecodeError::TruncatedCards {
+        offset: cursor.position(),
+    };
+    let overflow = DecodeError::ValueOverflow {
+        offset: card_offset,
+    };
+
+    if version >= 6 {
+        let current_byte: u8 =
+            cursor.get_u8().ok_or_else(|| truncated_cards(cursor))?;
+        let count_and_parallel_id_bits = current_byte >> 5;
+        let count_and_parallel_id = read_encoded_u32(
+            read_bits_from_byte(count_and_parallel_id_bits, 2, 0, 0),
+            count_and_parallel_id_bits,
+            3,
+            cursor,
+        )?;
+        let card_number_offset_bits = current_byte & 0x1F;
+        *prev_card_number = prev_card_number
+            .checked_add(read_encoded_u32(
+                read_bits_from_byte(card_number_offset_bits, 4, 0, 0),
+                card_number_offset_bits,
+                5,
+                cursor,
+            )?)
+            .ok_or_else(|| overflow.clone())?;
+        return Ok(Card {
+            number: format!(
+                "{s}-{:0>p$}",
+                prev_card_number,
+                s = card_set,
+                p = card_set_padding
+            ),
+            parallel_id: u8::try_from(count_and_parallel_id >> 8)
+                .map_err(|_| overflow.clone())?,
+            count: (count_and_parallel_id as u8)
+                .checked_add(1)
+                .ok_or(overflow)?,
+        });
+    }
+
+    let current_byte: u8 =
+        cursor.get_u8().ok_or_else(|| truncated_cards(cursor))?;
     let card_count = if version == 0 {
         (current_byte >> 6) + 1
     } else {
-        current_byte + 1
+        current_byte
+            .checked_add(1)
+            .ok_or_else(|| overflow.clone())?
     };
     let current_byte: u8 = if version == 0 {
         current_byte
     } else {
-        get_u8(&mut deck_bytes)
+        cursor.get_u8().ok_or_else(|| truncated_cards(cursor))?
     };
     let card_parallel_id = if version == 0 {
         current_byte >> 3 & 0x07
@@ -87,62 +305,85 @@ fn deserialize_card(
     let delta_shift: u8 = if version == 0 { 3 } else { 5 };
     let card_number: u32 =
         read_bits_from_byte(current_byte, delta_shift - 1, 0, 0);
-    *prev_card_number += read_encoded_u32(
-        card_number,
-        current_byte,
-        delta_shift,
-        &mut deck_bytes,
-    );
-
-    Card {
+    *prev_card_number = prev_card_number
+        .checked_add(read_encoded_u32(
+            card_number,
+            current_byte,
+            delta_shift,
+            cursor,
+        )?)
+        .ok_or(overflow)?;
+
+    Ok(Card {
         number: format!(
             "{s}-{:0>p$}",
             prev_card_number,
-            s = &card_set,
+            s = card_set,
             p = card_set_padding
         ),
         parallel_id: card_parallel_id,
         count: card_count,
-    }
+    })
+}
+
+/// Header fields shared by parse_deck and inspect
+struct Header {
+    version: u8,
+    checksum: u16,
+    digi_egg_count: usize,
+    deck_name_length: usize,
+    language: Option<Language>,
+    sideboard_count: usize,
+    has_icon: bool,
 }
 
-fn parse_deck(mut deck_bytes: &mut Vec<u8>) -> Deck {
-    let version_and_digi_egg_count = get_u8(&mut deck_bytes);
+fn parse_header(cursor: &mut DeckCursor) -> Result<Header, DecodeError> {
+    let truncated_header = |cursor: &DeckCursor| DecodeError::TruncatedHeader {
+        offset: cursor.position(),
+    };
+
+    let version_and_digi_egg_count =
+        cursor.get_u8().ok_or_else(|| truncated_header(cursor))?;
     let version = version_and_digi_egg_count >> 4;
-    assert!(VERSION >= version, "Deck version {} not supported", version);
+    if version > MAX_VERSION {
+        return Err(DecodeError::UnsupportedVersion { offset: 0, version });
+    }
 
-    let digi_egg_set_count = (version_and_digi_egg_count
-        & if 3 <= version && version <= 4 {
+    let digi_egg_count = (version_and_digi_egg_count
+        & if (3..=4).contains(&version) {
             0x07
         } else {
             0x0F
         }) as usize;
-    let checksum = get_u8(&mut deck_bytes);
-    let deck_name_length_byte = get_u8(&mut deck_bytes) as usize;
+    let mut checksum: u16 = cursor
+        .get_u8()
+        .ok_or_else(|| truncated_header(cursor))?
+        .into();
+    if version >= 7 {
+        checksum = checksum << 8
+            | u16::from(
+                cursor.get_u8().ok_or_else(|| truncated_header(cursor))?,
+            );
+    }
+    let deck_name_length_byte =
+        cursor.get_u8().ok_or_else(|| truncated_header(cursor))? as usize;
     let mut deck_name_length = deck_name_length_byte;
     if version >= 5 {
         deck_name_length &= 0x3F;
     }
-    let total_card_bytes = deck_bytes.len() - deck_name_length;
 
     let language_number = if version >= 5 {
         deck_name_length_byte >> 6
     } else {
         (version_and_digi_egg_count as usize >> 3) & 0x01
     };
-    let language = match language_number {
-        0 => Some(Language::Japanese),
-        1 => Some(Language::English),
-        2 => Some(Language::Chinese),
-        3 => Some(Language::Korean),
-        _ => Some(Language::English),
-    };
-
-    let computed_checksum = compute_checksum(total_card_bytes, deck_bytes);
-    assert_eq!(checksum, computed_checksum, "Deck checksum failed");
+    let mut language = Some(Language::from_number(language_number as u8));
 
     let mut sideboard_count: usize = if version >= 2 {
-        get_u8(&mut deck_bytes).into()
+        cursor
+            .get_u8()
+            .ok_or_else(|| truncated_header(cursor))?
+            .into()
     } else {
         0
     };
@@ -154,35 +395,132 @@ fn parse_deck(mut deck_bytes: &mut Vec<u8>) -> Deck {
         sideboard_count
     };
 
+    // v8 stores the language number + 1 in its own byte, 0 is unspecified
+    if version >= 8 {
+        let language_byte =
+            cursor.get_u8().ok_or_else(|| truncated_header(cursor))?;
+        language = language_byte.checked_sub(1).map(Language::from_number);
+    }
+
+    Ok(Header {
+        version,
+        checksum,
+        digi_egg_count,
+        deck_name_length,
+        language: if version >= 3 { language } else { None },
+        sideboard_count,
+        has_icon,
+    })
+}
+
+/// Split the trailing deck name bytes into the icon and deck name
+fn parse_name(
+    name_bytes: &[u8],
+    name_offset: usize,
+    has_icon: bool,
+) -> Result<(Option<String>, &str), DecodeError> {
+    let deck_name =
+        str::from_utf8(name_bytes).map_err(|_| DecodeError::InvalidName {
+            offset: name_offset,
+        })?;
+    if has_icon {
+        if deck_name.len() < 8 || !deck_name.is_char_boundary(8) {
+            return Err(DecodeError::IconOverflow {
+                offset: name_offset,
+            });
+        }
+        let (icon_raw, deck_name) = deck_name.split_at(8);
+        return Ok((Some(icon_raw.trim().to_string()), deck_name.trim()));
+    }
+    Ok((None, deck_name.trim()))
+}
+
+fn parse_deck(
+    deck_bytes: &[u8],
+) -> Result<(Deck, ChecksumAlgorithm), DecodeError> {
+    let mut cursor = DeckCursor::new(deck_bytes);
+    let truncated_cards = |cursor: &DeckCursor| DecodeError::TruncatedCards {
+        offset: cursor.position(),
+    };
+
+    let Header {
+        version,
+        checksum,
+        digi_egg_count,
+        deck_name_length,
+        language,
+        sideboard_count,
+        has_icon,
+    } = parse_header(&mut cursor)?;
+
+    let name_offset = deck_bytes
+        .len()
+        .checked_sub(deck_name_length)
+        .filter(|&name_offset| name_offset >= cursor.position())
+        .ok_or_else(|| truncated_cards(&cursor))?;
+
+    let checksum_algorithm = ChecksumAlgorithm::for_version(version);
+    let computed_checksum = match checksum_algorithm {
+        ChecksumAlgorithm::Additive => {
+            let total_card_bytes = name_offset - HEADER_SIZE;
+            compute_checksum(total_card_bytes, &deck_bytes[HEADER_SIZE..])
+                .into()
+        }
+        ChecksumAlgorithm::Crc16 => compute_crc16(deck_bytes),
+    };
+    if checksum != computed_checksum {
+        return Err(DecodeError::ChecksumMismatch {
+            offset: 1,
+            expected: checksum,
+            computed: computed_checksum,
+        });
+    }
+
     let mut cards: Vec<Card> = Vec::new();
 
-    while deck_bytes.len() > deck_name_length {
+    while cursor.position() < name_offset {
         // Card Set Header
         // - Card Set
+        let card_set_offset = cursor.position();
         let card_set = if version == 0 {
-            let card_set_bytes = get_u32(&mut deck_bytes);
-            get_string_from_bytes(card_set_bytes)
+            let card_set_bytes = cursor
+                .get_bytes(4)
+                .ok_or_else(|| truncated_cards(&cursor))?;
+            get_str_from_bytes(card_set_bytes)
+                .ok_or(DecodeError::InvalidCardSet {
+                    offset: card_set_offset,
+                })?
+                .to_string()
         } else {
             let mut s: String = Default::default();
             loop {
-                let current_byte: u8 = get_u8(&mut deck_bytes);
-                s += base36_to_char(current_byte & 0x3F);
+                let current_byte: u8 =
+                    cursor.get_u8().ok_or_else(|| truncated_cards(&cursor))?;
+                match base36_to_char(current_byte & 0x3F) {
+                    "" => {
+                        return Err(DecodeError::InvalidCardSet {
+                            offset: card_set_offset,
+                        })
+                    }
+                    chr => s += chr,
+                }
                 if current_byte >> 7 == 0 {
                     break;
                 }
             }
-            s.clone()
+            s
         };
         // - Card Set Zero Padding and Count
-        let padding_and_set_count = &get_u8(&mut deck_bytes);
+        let padding_and_set_count =
+            cursor.get_u8().ok_or_else(|| truncated_cards(&cursor))?;
         let card_set_padding = ((padding_and_set_count >> 6) + 1) as usize;
         let card_set_count: u32 = if version >= 2 {
             read_encoded_u32(
-                *padding_and_set_count as u32,
-                *padding_and_set_count,
+                read_bits_from_byte(padding_and_set_count, 5, 0, 0),
+                padding_and_set_count,
                 6,
-                &mut deck_bytes,
-            )
+                &mut cursor,
+            )?
         } else {
             (padding_and_set_count & 0x3F).into()
         };
@@ -190,45 +528,129 @@ fn parse_deck(mut deck_bytes: &mut Vec<u8>) -> Deck {
         let mut prev_card_number: u32 = 0;
 
         for _ in 0..card_set_count {
+            let card_offset = cursor.position();
             let card = deserialize_card(
                 version,
-                &mut deck_bytes,
+                &mut cursor,
                 &card_set,
                 card_set_padding,
                 &mut prev_card_number,
-            );
+            )?;
+            // a card number wider than its zero padding would be re-encoded
+            // in a different card group
+            if prev_card_number >= 10_u32.pow(card_set_padding as u32) {
+                return Err(DecodeError::InvalidCardNumber {
+                    offset: card_offset,
+                });
+            }
             cards.push(card);
         }
     }
 
-    let mut icon = None;
-    let mut deck_name = get_string_from_bytes(deck_bytes.to_vec());
-    if has_icon {
-        let (icon_raw, new_deck_name) = deck_name.split_at(8);
-        icon = Some(icon_raw.trim().to_string());
-        deck_name = new_deck_name.trim().to_string();
+    // Cards must end exactly where the deck name begins and account for
+    // every digi-egg and sideboard card
+    if cursor.position() != name_offset
+        || digi_egg_count + sideboard_count > cards.len()
+    {
+        return Err(truncated_cards(&cursor));
     }
 
-    Deck {
-        digi_eggs: (&cards[..digi_egg_set_count]).to_vec(),
-        deck: (&cards[digi_egg_set_count..(cards.len() - sideboard_count)])
-            .to_vec(),
-        sideboard: if version >= 2 && sideboard_count != 0 {
-            (&cards[(cards.len() - sideboard_count)..]).to_vec()
-        } else {
-            Vec::<Card>::new()
+    let (icon, deck_name) = parse_name(cursor.rest(), name_offset, has_icon)?;
+
+    let sideboard = if version >= 2 && sideboard_count != 0 {
+        cards.split_off(cards.len() - sideboard_count)
+    } else {
+        Vec::<Card>::new()
+    };
+    let deck = cards.split_off(digi_egg_count);
+
+    Ok((
+        Deck {
+            digi_eggs: cards,
+            deck,
+            sideboard,
+            icon,
+            language,
+            name: deck_name.to_string(),
         },
-        icon: icon,
-        language: if version >= 3 { language } else { None },
-        name: deck_name.to_string(),
-    }
+        checksum_algorithm,
+    ))
 }
 
-pub fn decode(deck_code_str: &str) -> Deck {
+fn decode_deck_bytes(deck_code_str: &str) -> Result<Vec<u8>, DecodeError> {
+    let deck_code = deck_code_str
+        .strip_prefix(PREFIX)
+        .ok_or(DecodeError::BadPrefix { offset: 0 })?;
+
+    URL_SAFE_NO_PAD.decode(deck_code).map_err(|e| {
+        let offset = match e {
+            base64::DecodeError::InvalidByte(offset, _)
+            | base64::DecodeError::InvalidLastSymbol(offset, _) => offset,
+            _ => deck_code.len(),
+        };
+        DecodeError::BadBase64 {
+            offset: PREFIX.len() + offset,
+        }
+    })
+}
+
+pub fn inspect(deck_code_str: &str) -> Result<DeckHeader, DecodeError> {
+    //! Read the header and deck name of a deck code without parsing its cards
+    //!
+    //! The checksum is returned as stored and is not verified.
+    let deck_bytes = decode_deck_bytes(deck_code_str)?;
+    let mut cursor = DeckCursor::new(&deck_bytes);
+    let Header {
+        version,
+        checksum,
+        digi_egg_count,
+        deck_name_length,
+        language,
+        sideboard_count,
+        has_icon,
+    } = parse_header(&mut cursor)?;
+
+    let name_offset = deck_bytes
+        .len()
+        .checked_sub(deck_name_length)
+        .filter(|&name_offset| name_offset >= cursor.position())
+        .ok_or(DecodeError::TruncatedCards {
+            offset: cursor.position(),
+        })?;
+    let (icon, name) =
+        parse_name(&deck_bytes[name_offset..], name_offset, has_icon)?;
+
+    Ok(DeckHeader {
+        version,
+        checksum,
+        checksum_algorithm: ChecksumAlgorithm::for_version(version),
+        language,
+        digi_egg_count,
+        sideboard_count,
+        has_icon,
+        icon,
+        name: name.to_string(),
+    })
+}
+
+pub fn try_decode(deck_code_str: &str) -> Result<Deck, DecodeError> {
     //! Decode public function that takes a deck code and decodes to a Deck struct
-    let (prefix, deck_code) = deck_code_str.split_at(3);
-    assert_eq!(PREFIX, prefix, "Prefix was not 'DCG'");
+    //! or returns the DecodeError describing where parsing failed
+    try_decode_verified(deck_code_str).map(|(deck, _)| deck)
+}
+
+pub fn try_decode_verified(
+    deck_code_str: &str,
+) -> Result<(Deck, ChecksumAlgorithm), DecodeError> {
+    //! Decode a deck code to a Deck struct along with the checksum algorithm
+    //! that was verified
+    let deck_bytes = decode_deck_bytes(deck_code_str)?;
+    parse_deck(&deck_bytes)
+}
 
-    let mut deck_bytes: Vec<u8> = URL_SAFE_NO_PAD.decode(deck_code).unwrap();
-    parse_deck(&mut deck_bytes)
+pub fn decode(deck_code_str: &str) -> Deck {
+    //! Decode public function that takes a deck code and decodes to a Deck struct
+    //!
+    //! Panics if the deck code is invalid. Use try_decode to handle errors.
+    try_decode(deck_code_str).unwrap_or_else(|e| panic!("{}", e))
 }
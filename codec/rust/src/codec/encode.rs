@@ -1,11 +1,240 @@
 //! Encoder
 
+use crate::codec::card_number::CardNumberParts;
 pub use crate::codec::{
-    char_to_base36, compute_checksum, Card, Deck, Language, HEADER_SIZE,
+    card_order, char_to_base36, compute_checksum, compute_crc16, Card,
+    CardNumber, ChecksumAlgorithm, Deck, Language, HEADER_SIZE, MAX_VERSION,
     PREFIX, VERSION,
 };
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
-use std::str;
+use base64::{
+    display::Base64Display, engine::general_purpose::URL_SAFE_NO_PAD,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// Errors that can occur while encoding a deck
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// version is newer than MAX_VERSION
+    UnsupportedVersion {
+        /// requested version
+        version: u8,
+    },
+    /// card number is not a card set and a number separated by a hyphen
+    InvalidCardNumber {
+        /// card number identifier
+        number: String,
+    },
+    /// card count is outside of the range the version can store
+    InvalidCardCount {
+        /// card number identifier
+        number: String,
+        /// card count
+        count: u8,
+    },
+    /// parallel id is outside of the range the version can store
+    InvalidParallelId {
+        /// card number identifier
+        number: String,
+        /// parallel id
+        parallel_id: u8,
+    },
+    /// more Card entries than the version can store in a deck section or
+    /// card group
+    TooManyCards {
+        /// count of Card entries
+        count: usize,
+        /// most Card entries the version can store
+        max: usize,
+    },
+    /// icon doesn't fit in the 8 bytes stored before the deck name
+    InvalidIcon {
+        /// deck icon
+        icon: String,
+    },
+    /// deck has features the version can't store
+    Lossy {
+        /// requested version
+        version: u8,
+        /// deck features that would be dropped
+        warnings: Vec<LossWarning>,
+    },
+    /// deck code could not be written to the output
+    Write,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::UnsupportedVersion { version } => {
+                write!(f, "Deck version {} not supported", version)
+            }
+            EncodeError::InvalidCardNumber { number } => {
+                write!(f, "Invalid card number '{}'", number)
+            }
+            EncodeError::InvalidCardCount { number, count } => {
+                write!(f, "Invalid card count {} for '{}'", count, number)
+            }
+            EncodeError::InvalidParallelId {
+                number,
+                parallel_id,
+            } => {
+                write!(
+                    f,
+                    "Invalid parallel id {} for '{}'",
+                    parallel_id, number
+                )
+            }
+            EncodeError::TooManyCards { count, max } => {
+                write!(f, "{} cards exceed the limit of {}", count, max)
+            }
+            EncodeError::InvalidIcon { icon } => {
+                write!(f, "Icon '{}' is longer than 8 bytes", icon)
+            }
+            EncodeError::Lossy { version, warnings } => {
+                write!(f, "Deck version {} can't store the deck:", version)?;
+                for warning in warnings {
+                    write!(f, " {};", warning)?;
+                }
+                Ok(())
+            }
+            EncodeError::Write => write!(f, "Failed to write deck code"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Deck features that are dropped when encoding to an older version
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LossWarning {
+    /// icon is only stored since v4
+    Icon(String),
+    /// language is only stored since v3
+    Language(Language),
+    /// v3 and v4 only store Japanese or English, and v5 to v7 only store
+    /// the four known languages
+    LanguageNarrowed {
+        /// deck language
+        from: Language,
+        /// language stored in the deck code
+        to: Language,
+    },
+    /// v3 to v7 always store a language, so a deck without one is stored
+    /// as English
+    UnspecifiedLanguage,
+    /// sideboard is only stored since v2
+    Sideboard(Vec<Card>),
+}
+
+impl fmt::Display for LossWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LossWarning::Icon(icon) => write!(f, "Icon '{}' dropped", icon),
+            LossWarning::Language(language) => {
+                write!(f, "Language {} dropped", language)
+            }
+            LossWarning::LanguageNarrowed { from, to } => {
+                write!(f, "Language {} stored as {}", from, to)
+            }
+            LossWarning::UnspecifiedLanguage => write!(
+                f,
+                "Unspecified language stored as {}",
+                Language::English
+            ),
+            LossWarning::Sideboard(cards) => {
+                write!(f, "Sideboard of {} cards dropped", cards.len())
+            }
+        }
+    }
+}
+
+/// Highest language number a deck code of `version` can store
+const fn max_language_number(version: u8) -> u8 {
+    match version {
+        // 1 bit for Japanese or English
+        3..=4 => 1,
+        // 2 bits
+        5..=7 => 3,
+        // 1 byte with 0 for an unspecified language
+        _ => u8::MAX - 1,
+    }
+}
+
+/// List the deck features that can't be stored in a deck code of `version`
+pub fn loss_warnings(deck: &Deck, version: u8) -> Vec<LossWarning> {
+    let mut warnings = Vec::new();
+    if version < 4 {
+        if let Some(icon) = &deck.icon {
+            warnings.push(LossWarning::Icon(icon.clone()));
+        }
+    }
+    match &deck.language {
+        Some(language) if version < 3 => {
+            warnings.push(LossWarning::Language(language.clone()))
+        }
+        Some(language) if language.number() > max_language_number(version) => {
+            warnings.push(LossWarning::LanguageNarrowed {
+                from: language.clone(),
+                to: Language::English,
+            })
+        }
+        None if (3..=7).contains(&version) => {
+            warnings.push(LossWarning::UnspecifiedLanguage)
+        }
+        _ => {}
+    }
+    if version < 2 && !deck.sideboard.is_empty() {
+        warnings.push(LossWarning::Sideboard(deck.sideboard.clone()));
+    }
+    warnings
+}
+
+/// Parts of a card number a deck code of `version` stores unchanged
+fn encodable_card_number(
+    card: &Card,
+    version: u8,
+) -> Result<CardNumberParts<'_>, EncodeError> {
+    // card sets, zero padding and suffixes that can't be stored would
+    // decode to a different card
+    CardNumberParts::parse(&card.number)
+        .filter(|card_number| card_number.is_encodable(version))
+        .ok_or_else(|| EncodeError::InvalidCardNumber {
+            number: card.number.clone(),
+        })
+}
+
+fn validate_card(card: &Card, version: u8) -> Result<(), EncodeError> {
+    encodable_card_number(card, version)?;
+    // v0 stores card count in 2 bits
+    if card.count == 0 || (version == 0 && card.count > 4) {
+        return Err(EncodeError::InvalidCardCount {
+            number: card.number.clone(),
+            count: card.count,
+        });
+    }
+    // v0-v5 store parallel id in 3 bits
+    if version < 6 && card.parallel_id > 0x07 {
+        return Err(EncodeError::InvalidParallelId {
+            number: card.number.clone(),
+            parallel_id: card.parallel_id,
+        });
+    }
+    Ok(())
+}
+
+/// Truncate a string to at most `max_bytes` bytes on a char boundary
+fn truncate_on_char_boundary(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
 
 fn bits_with_carry(value: u32, bits: u8) -> u8 {
     let limit_bit = 1 << (bits - 1);
@@ -24,98 +253,245 @@ fn append_rest_to_deck_bytes(
     let mut remaining_value = value >> (already_written_bits - 1);
     while remaining_value > 0 {
         deck_bytes.push(bits_with_carry(remaining_value, u8::BITS as u8));
-        remaining_value = remaining_value >> 7;
-    }
-}
-
-fn group_cards(mut cards: Vec<Card>) -> Vec<(String, usize, Vec<Card>)> {
-    cards.sort_unstable_by_key(|card: &Card| {
-        (card.number.clone(), card.parallel_id)
-    });
-    let mut result: Vec<(String, usize, Vec<Card>)> = Vec::new();
-    let mut grouped_cards: Vec<Card> = Vec::new();
-    let mut prev_card_set: Option<String> = None;
-    let mut prev_card_set_number_padding: Option<usize> = None;
-    for card in cards {
-        let split_result: Vec<&str> = card.number.as_str().split("-").collect();
-        let card_set = split_result[0].to_string();
-        let card_set_number_padding = split_result[1].len();
-        let same_card_set = match prev_card_set {
-            Some(ref x) => *x == card_set,
-            _ => true,
-        };
-        let same_card_set_number_padding = match prev_card_set_number_padding {
-            Some(x) => x == card_set_number_padding,
-            _ => true,
-        };
-        if same_card_set && same_card_set_number_padding {
-            grouped_cards.push(card);
+        remaining_value >>= 7;
+    }
+}
+
+/// A card of a deck section with the parts of its card number, parsed once
+/// per encode
+#[derive(Clone, Copy, Debug)]
+struct SortedCard {
+    /// index of the card in its deck section
+    index: usize,
+    /// byte length of the card set at the start of the card number
+    card_set_len: usize,
+    /// count of digits in the numeric part including zero padding
+    padding: usize,
+    /// numeric part of the card number
+    number: u32,
+}
+
+impl SortedCard {
+    fn card_set<'a>(&self, cards: &'a [Card]) -> &'a str {
+        &cards[self.index].number[..self.card_set_len]
+    }
+}
+
+/// Parse every card number of a deck section and sort the cards the way
+/// they are stored in a deck code
+fn sort_cards(
+    sorted_cards: &mut Vec<SortedCard>,
+    cards: &[Card],
+    version: u8,
+) -> Result<(), EncodeError> {
+    sorted_cards.clear();
+    for (index, card) in cards.iter().enumerate() {
+        let card_number = encodable_card_number(card, version)?;
+        sorted_cards.push(SortedCard {
+            index,
+            card_set_len: card_number.card_set.len(),
+            padding: card_number.padding,
+            number: card_number.number,
+        });
+    }
+    sorted_cards
+        .sort_unstable_by(|a, b| card_order(&cards[a.index], &cards[b.index]));
+    Ok(())
+}
+
+/// Cards belong to the same group when they share a card set and zero padding
+fn same_card_group(cards: &[Card], a: &SortedCard, b: &SortedCard) -> bool {
+    a.card_set(cards) == b.card_set(cards) && a.padding == b.padding
+}
+
+fn encode_card_group(
+    deck_bytes: &mut Vec<u8>,
+    cards: &[Card],
+    grouped_cards: &[SortedCard],
+    version: u8,
+) -> Result<(), EncodeError> {
+    let card_set = grouped_cards[0].card_set(cards);
+    let card_number_padding = grouped_cards[0].padding;
+    // Encode card_set
+    if version == 0 {
+        // Use 4 characters/bytes to store card sets.
+        deck_bytes
+            .extend(card_set.bytes().chain(std::iter::repeat(b' ')).take(4));
+    } else {
+        // Encode each character of card-set in Base36.
+        // Use 8th bit as continue bit. If 0, reached end.
+        let mut chr_iterator = card_set.chars().peekable();
+        while let Some(chr) = chr_iterator.next() {
+            let mut base36_char = char_to_base36(&chr);
+            if chr_iterator.peek().is_some() {
+                base36_char |= 0x80;
+            }
+            deck_bytes.push(base36_char);
+        }
+    }
+    // 2 bits for card number zero padding (zero padding stored as 0 indexed)
+    // 6 bits for initial count offset of cards in a card group
+    if version < 2 {
+        if grouped_cards.len() > 0x3F {
+            return Err(EncodeError::TooManyCards {
+                count: grouped_cards.len(),
+                max: 0x3F,
+            });
+        }
+        deck_bytes.push(
+            (card_number_padding as u8 - 1) << 6 | grouped_cards.len() as u8,
+        );
+    } else {
+        deck_bytes.push(
+            (card_number_padding as u8 - 1) << 6
+                | bits_with_carry(grouped_cards.len() as u32, 6),
+        );
+        append_rest_to_deck_bytes(deck_bytes, grouped_cards.len() as u32, 6);
+    }
+    let mut prev_card_number = 0;
+    for sorted_card in grouped_cards {
+        let card = &cards[sorted_card.index];
+        let card_set_number = sorted_card.number;
+        let card_number_offset = card_set_number - prev_card_number;
+        if version == 0 {
+            // 2 bits for card count (1-4)
+            // 3 bits for parallel id (0-7)
+            // 3 bits for start of card number offset
+            deck_bytes.push(
+                (card.count - 1) << 6
+                    | card.parallel_id << 3
+                    | bits_with_carry(card_number_offset, 3),
+            );
+            // rest of card number offset
+            append_rest_to_deck_bytes(deck_bytes, card_number_offset, 3);
+        } else if version >= 6 {
+            // card count and parallel id share one value with the card count
+            // (1-50 with BT6-085) in the lowest byte
+            let count_and_parallel_id =
+                u32::from(card.count - 1) | u32::from(card.parallel_id) << 8;
+            // 3 bits for start of card count and parallel id
+            // 5 bits for start of card number offset
+            deck_bytes.push(
+                bits_with_carry(count_and_parallel_id, 3) << 5
+                    | bits_with_carry(card_number_offset, 5),
+            );
+            // rest of card count and parallel id
+            append_rest_to_deck_bytes(deck_bytes, count_and_parallel_id, 3);
+            // rest of card number offset
+            append_rest_to_deck_bytes(deck_bytes, card_number_offset, 5);
         } else {
-            result.push((
-                prev_card_set.clone().unwrap(),
-                prev_card_set_number_padding.clone().unwrap(),
-                grouped_cards.clone(),
-            ));
-            grouped_cards.clear();
-            grouped_cards.push(card);
+            // 1 byte for card count (1-50 with BT6-085)
+            // 3 bits for parallel id (0-7)
+            // 5 bits for start of card number offset
+            deck_bytes.push(card.count - 1);
+            deck_bytes.push(
+                card.parallel_id << 5 | bits_with_carry(card_number_offset, 5),
+            );
+            // rest of card number offset
+            append_rest_to_deck_bytes(deck_bytes, card_number_offset, 5);
+        }
+        prev_card_number = card_set_number;
+    }
+    Ok(())
+}
+
+/// Check that every feature of the deck can be stored in `version`
+fn check_representable(deck: &Deck, version: u8) -> Result<(), EncodeError> {
+    if version > MAX_VERSION {
+        return Err(EncodeError::UnsupportedVersion { version });
+    }
+    let warnings = loss_warnings(deck, version);
+    if !warnings.is_empty() {
+        return Err(EncodeError::Lossy { version, warnings });
+    }
+    for card in deck
+        .digi_eggs
+        .iter()
+        .chain(&deck.deck)
+        .chain(&deck.sideboard)
+    {
+        validate_card(card, version)?;
+    }
+    // icon is stored as the first 8 bytes of the deck name
+    if let Some(icon) = &deck.icon {
+        if version >= 4 && icon.trim().len() > ICON_SIZE {
+            return Err(EncodeError::InvalidIcon { icon: icon.clone() });
         }
-        prev_card_set = Some(card_set.clone());
-        prev_card_set_number_padding = Some(card_set_number_padding);
     }
-    if grouped_cards.len() > 0 {
-        result.push((
-            prev_card_set.clone().unwrap(),
-            prev_card_set_number_padding.clone().unwrap(),
-            grouped_cards.clone(),
-        ));
+    // 4 bits for digi-egg count (3 bits in v3 and v4)
+    let max_digi_eggs = if (3..=4).contains(&version) {
+        0x07
+    } else {
+        0x0F
+    };
+    if deck.digi_eggs.len() > max_digi_eggs {
+        return Err(EncodeError::TooManyCards {
+            count: deck.digi_eggs.len(),
+            max: max_digi_eggs,
+        });
     }
-    result
+    // 8 bits for sideboard count (7 bits since v4)
+    let max_sideboard = if version >= 4 { 0x7F } else { 0xFF };
+    if deck.sideboard.len() > max_sideboard {
+        return Err(EncodeError::TooManyCards {
+            count: deck.sideboard.len(),
+            max: max_sideboard,
+        });
+    }
+    Ok(())
 }
 
-pub fn encode(
+/// Bytes of the deck name used by the icon
+const ICON_SIZE: usize = 8;
+
+fn write_deck_bytes(
     Deck {
         digi_eggs,
         deck,
         sideboard,
         language,
         icon,
-        mut name,
-    }: Deck,
+        name,
+    }: &Deck,
     version: u8,
-) -> String {
-    //! Encode public function that takes a Deck struct and encodes to a deck code string
-    let mut deck_bytes: Vec<u8> = Vec::<u8>::new();
-
-    let language_number = match language {
-        Some(Language::Japanese) => 0,
-        Some(Language::English) => 1,
-        Some(Language::Chinese) => 2,
-        Some(Language::Korean) => 3,
-        _ => 1,
-    };
+    deck_bytes: &mut Vec<u8>,
+    sorted_cards: &mut Vec<SortedCard>,
+) -> Result<(), EncodeError> {
+    let header_start = deck_bytes.len();
 
-    let version_and_digi_egg_count = if 3 <= version && version <= 4 {
-        version << 4 | language_number << 3 | digi_eggs.len() as u8 & 0x0F
+    // v3-v7 can't store an unspecified language, which loss_warnings reports
+    let language_number = language.as_ref().map_or(1, Language::number);
+
+    let version_and_digi_egg_count = if (3..=4).contains(&version) {
+        // Only 1 bit for language (Japanese or English)
+        let language_bit = (language_number != 0) as u8;
+        version << 4 | language_bit << 3 | digi_eggs.len() as u8 & 0x07
     } else {
         version << 4 | digi_eggs.len() as u8 & 0x0F
     };
 
-    if version >= 4 && icon.is_some() {
-        let mut icon_string = String::from("        ");
-        icon_string = icon.clone().unwrap() + &icon_string;
-        icon_string = icon_string.split_at(8).0.to_string();
-        name = icon_string + &name;
+    // Icon is stored as the first 8 bytes of the deck name
+    // padded with spaces by bytes, not chars, so multibyte icons fill
+    // exactly 8 bytes
+    let mut icon_string = String::new();
+    if let Some(icon) = icon.as_deref().filter(|_| version >= 4) {
+        let icon = icon.trim();
+        icon_string.push_str(icon);
+        icon_string.push_str(&" ".repeat(ICON_SIZE - icon.len()));
     }
-    let mut deck_name_bytes = name.as_bytes().to_vec();
-    deck_name_bytes.truncate(0x3F);
-    name = str::from_utf8(&deck_name_bytes).unwrap().trim().to_string();
-    let mut name_length = deck_name_bytes.len() as u8;
-    if version >= 5 {
-        name_length = language_number << 6 | name_length;
+    let name = truncate_on_char_boundary(name.trim(), 0x3F - icon_string.len())
+        .trim_end();
+    let name = icon_string + name;
+    let mut name_length = name.len() as u8;
+    if (5..=7).contains(&version) {
+        name_length |= language_number << 6;
     }
 
     deck_bytes.push(version_and_digi_egg_count);
     deck_bytes.push(0); // checksum placeholder
+    if version >= 7 {
+        deck_bytes.push(0); // CRC-16 checksum placeholder
+    }
     deck_bytes.push(name_length);
 
     if version >= 2 {
@@ -125,103 +501,174 @@ pub fn encode(
         }
         deck_bytes.push(sideboard_size);
     }
+    if version >= 8 {
+        deck_bytes.push(language.as_ref().map_or(0, |l| l.number() + 1));
+    }
 
-    let mut grouped_decks = Vec::new();
-    grouped_decks.append(&mut group_cards(digi_eggs.clone()));
-    grouped_decks.append(&mut group_cards(deck.clone()));
-    grouped_decks.append(&mut group_cards(sideboard.clone()));
+    for cards in [digi_eggs, deck, sideboard] {
+        sort_cards(sorted_cards, cards, version)?;
+        for grouped_cards in
+            sorted_cards.chunk_by(|a, b| same_card_group(cards, a, b))
+        {
+            encode_card_group(deck_bytes, cards, grouped_cards, version)?;
+        }
+    }
 
-    for (card_set, card_number_padding, grouped_cards) in grouped_decks {
-        // Encode card_set
-        if version == 0 {
-            // Use 4 characters/bytes to store card sets.
-            let mut card_set_bytes =
-                format!("{: <4}", card_set).as_bytes().to_vec();
-            deck_bytes.append(&mut card_set_bytes);
-        } else {
-            // Encode each character of card-set in Base36.
-            // Use 8th bit as continue bit. If 0, reached end.
-            let mut chr_iterator = card_set.chars().peekable();
-            while let Some(chr) = chr_iterator.next() {
-                let mut base36_char = char_to_base36(&chr);
-                if chr_iterator.peek().is_some() {
-                    base36_char |= 0x80;
-                }
-                deck_bytes.push(base36_char);
-            }
+    match ChecksumAlgorithm::for_version(version) {
+        ChecksumAlgorithm::Additive => {
+            // Compute and store cards checksum (second byte in buffer)
+            // Only store the first byte of checksum
+            let cards_start = header_start + HEADER_SIZE;
+            let total_card_bytes = deck_bytes.len() - cards_start;
+            let computed_checksum =
+                compute_checksum(total_card_bytes, &deck_bytes[cards_start..]);
+            deck_bytes[header_start + 1] = computed_checksum;
+
+            deck_bytes.extend_from_slice(name.as_bytes());
         }
-        // 2 bits for card number zero padding (zero padding stored as 0 indexed)
-        // 6 bits for initial count offset of cards in a card group
-        if version < 2 {
-            deck_bytes.push(
-                (card_number_padding as u8 - 1) << 6
-                    | grouped_cards.len() as u8,
-            );
-        } else {
-            deck_bytes.push(
-                (card_number_padding as u8 - 1) << 6
-                    | bits_with_carry(grouped_cards.len() as u32, 6),
-            );
-            append_rest_to_deck_bytes(
-                &mut deck_bytes,
-                grouped_cards.len() as u32,
-                6,
-            );
+        ChecksumAlgorithm::Crc16 => {
+            // Compute and store CRC-16 of the whole deck code (second and
+            // third bytes in buffer) once the deck name is written
+            deck_bytes.extend_from_slice(name.as_bytes());
+
+            let computed_checksum = compute_crc16(&deck_bytes[header_start..]);
+            deck_bytes[header_start + 1..header_start + 3]
+                .copy_from_slice(&computed_checksum.to_be_bytes()[..]);
         }
-        let mut prev_card_number = 0;
-        for card in &grouped_cards {
-            let split_result: Vec<&str> =
-                card.number.as_str().split("-").collect();
-            let card_set_number: u32 = split_result[1].parse().unwrap();
-            let card_number_offset = card_set_number - prev_card_number;
-            if version == 0 {
-                // 2 bits for card count (1-4)
-                // 3 bits for parallel id (0-7)
-                // 3 bits for start of card number offset
-                deck_bytes.push(
-                    (card.count - 1) << 6
-                        | card.parallel_id << 3
-                        | bits_with_carry(card_number_offset as u32, 3),
-                );
-                // rest of card number offset
-                append_rest_to_deck_bytes(
-                    &mut deck_bytes,
-                    card_number_offset as u32,
-                    3,
-                );
-            } else {
-                // TODO: Consider encoding card count with carry bit
-                // so it takes less space when count is less than 5 bits?
-
-                // 1 byte for card count (1-50 with BT6-085)
-                // 3 bits for parallel id (0-7)
-                // 5 bits for start of card number offset
-                deck_bytes.push(card.count - 1);
-                deck_bytes.push(
-                    card.parallel_id << 5
-                        | bits_with_carry(card_number_offset as u32, 5),
-                );
-                // rest of card number offset
-                append_rest_to_deck_bytes(
-                    &mut deck_bytes,
-                    card_number_offset as u32,
-                    5,
-                );
-            }
-            prev_card_number = card_set_number;
+    }
+
+    Ok(())
+}
+
+/// Encoder that keeps its byte and card sort buffers between decks, so
+/// encoding many decks doesn't allocate once the buffers have grown
+#[derive(Clone, Debug, Default)]
+pub struct Encoder {
+    deck_bytes: Vec<u8>,
+    sorted_cards: Vec<SortedCard>,
+}
+
+impl Encoder {
+    /// Encoder with empty buffers
+    pub fn new() -> Self {
+        Encoder::default()
+    }
+
+    /// Encode a borrowed Deck struct and append the deck code bytes (before
+    /// base64 encoding and without PREFIX) to a caller-provided buffer
+    ///
+    /// Returns an EncodeError without appending anything when `version`
+    /// can't store every feature of the deck.
+    pub fn encode_bytes_into(
+        &mut self,
+        deck: &Deck,
+        version: u8,
+        deck_bytes: &mut Vec<u8>,
+    ) -> Result<(), EncodeError> {
+        check_representable(deck, version)?;
+        let start = deck_bytes.len();
+        write_deck_bytes(deck, version, deck_bytes, &mut self.sorted_cards)
+            .inspect_err(|_| {
+                deck_bytes.truncate(start);
+            })
+    }
+
+    /// Encode a borrowed Deck struct and write the deck code string to `out`
+    pub fn encode_into<W: fmt::Write>(
+        &mut self,
+        deck: &Deck,
+        version: u8,
+        out: &mut W,
+    ) -> Result<(), EncodeError> {
+        let mut deck_bytes = std::mem::take(&mut self.deck_bytes);
+        deck_bytes.clear();
+        let result = self
+            .encode_bytes_into(deck, version, &mut deck_bytes)
+            .and_then(|_| {
+                write!(
+                    out,
+                    "{}{}",
+                    PREFIX,
+                    Base64Display::new(&deck_bytes, &URL_SAFE_NO_PAD)
+                )
+                .map_err(|_| EncodeError::Write)
+            });
+        self.deck_bytes = deck_bytes;
+        result
+    }
+}
+
+pub fn encode_bytes_into(
+    deck: &Deck,
+    version: u8,
+    deck_bytes: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    //! Encode a borrowed Deck struct and append the deck code bytes (before
+    //! base64 encoding and without PREFIX) to a caller-provided buffer
+    //!
+    //! Use an Encoder to reuse its card sort buffer across decks.
+    Encoder::new().encode_bytes_into(deck, version, deck_bytes)
+}
+
+pub fn encode_into<W: fmt::Write>(
+    deck: &Deck,
+    version: u8,
+    out: &mut W,
+) -> Result<(), EncodeError> {
+    //! Encode a borrowed Deck struct and write the deck code string to `out`
+    //!
+    //! Use an Encoder to reuse its buffers across decks.
+    Encoder::new().encode_into(deck, version, out)
+}
+
+pub fn try_encode(deck: Deck, version: u8) -> Result<String, EncodeError> {
+    //! Encode public function that takes a Deck struct and encodes to a deck code string
+    //! or returns the EncodeError for the first card that can't be encoded
+    let mut deck_code = String::new();
+    encode_into(&deck, version, &mut deck_code)?;
+    Ok(deck_code)
+}
+
+pub fn minimal_version(deck: &Deck) -> Result<u8, EncodeError> {
+    //! Lowest version up to VERSION that can store every feature of the deck
+    //!
+    //! v8 is never picked since other implementations can't read it yet, so
+    //! decks only v8 can store return the error of VERSION.
+    let mut result = Err(EncodeError::UnsupportedVersion { version: 0 });
+    for version in 0..=VERSION {
+        result = check_representable(deck, version).map(|_| version);
+        if result.is_ok() {
+            break;
         }
     }
+    result
+}
 
-    // Compute and store cards checksum (second byte in buffer)
-    // Only store the first byte of checksum
-    let total_card_bytes = deck_bytes.len() - HEADER_SIZE;
-    let computed_checksum =
-        compute_checksum(total_card_bytes, &deck_bytes.clone()[HEADER_SIZE..]);
-    deck_bytes[1] = (computed_checksum & 0xFF) as u8;
+pub fn encode_canonical(
+    deck: &Deck,
+    version: u8,
+) -> Result<String, EncodeError> {
+    //! Encode a canonicalized copy of a borrowed Deck struct so that decks
+    //! with the same cards always encode to the same deck code
+    let mut deck = deck.clone();
+    deck.canonicalize();
+    let mut deck_code = String::new();
+    encode_into(&deck, version, &mut deck_code)?;
+    Ok(deck_code)
+}
 
-    deck_bytes.append(&mut name.as_bytes().to_vec());
+pub fn encode_minimal(deck: &Deck) -> Result<String, EncodeError> {
+    //! Encode a borrowed Deck struct using the lowest version that can store
+    //! every feature of the deck
+    let version = minimal_version(deck)?;
+    let mut deck_code = String::new();
+    encode_into(deck, version, &mut deck_code)?;
+    Ok(deck_code)
+}
 
-    let deck_b64_encoded = URL_SAFE_NO_PAD.encode(deck_bytes);
-    let deck_string = PREFIX.to_string() + &deck_b64_encoded;
-    deck_string
+pub fn encode(deck: Deck, version: u8) -> String {
+    //! Encode public function that takes a Deck struct and encodes to a deck code string
+    //!
+    //! Panics if the deck can't be encoded. Use try_encode to handle errors.
+    try_encode(deck, version).unwrap_or_else(|e| panic!("{}", e))
 }
@@ -0,0 +1,69 @@
+//! Transcoder
+
+pub use crate::codec::{
+    loss_warnings, try_decode, try_encode, Deck, DecodeError, EncodeError,
+    Language, LossWarning,
+};
+use std::fmt;
+
+/// Errors that can occur while transcoding a deck code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscodeError {
+    /// deck code could not be decoded
+    Decode(DecodeError),
+    /// deck could not be encoded to the target version
+    Encode(EncodeError),
+}
+
+impl fmt::Display for TranscodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscodeError::Decode(e) => e.fmt(f),
+            TranscodeError::Encode(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TranscodeError {}
+
+impl From<DecodeError> for TranscodeError {
+    fn from(e: DecodeError) -> Self {
+        TranscodeError::Decode(e)
+    }
+}
+
+impl From<EncodeError> for TranscodeError {
+    fn from(e: EncodeError) -> Self {
+        TranscodeError::Encode(e)
+    }
+}
+
+/// Remove the deck features listed in `warnings`
+fn drop_losses(deck: &mut Deck, warnings: &[LossWarning]) {
+    for warning in warnings {
+        match warning {
+            LossWarning::Icon(_) => deck.icon = None,
+            LossWarning::Language(_) => deck.language = None,
+            LossWarning::LanguageNarrowed { to, .. } => {
+                deck.language = Some(to.clone())
+            }
+            LossWarning::UnspecifiedLanguage => {
+                deck.language = Some(Language::English)
+            }
+            LossWarning::Sideboard(_) => deck.sideboard.clear(),
+        }
+    }
+}
+
+pub fn transcode(
+    deck_code_str: &str,
+    target_version: u8,
+) -> Result<(String, Vec<LossWarning>), TranscodeError> {
+    //! Decode a deck code of any supported version and re-encode it to
+    //! `target_version`, listing the deck features that were dropped
+    let mut deck = try_decode(deck_code_str)?;
+    let warnings = loss_warnings(&deck, target_version);
+    drop_losses(&mut deck, &warnings);
+    let deck_code = try_encode(deck, target_version)?;
+    Ok((deck_code, warnings))
+}
@@ -0,0 +1,184 @@
+//! Deck differences
+
+use crate::codec::{Card, Deck, Language};
+use crate::normalize::copy_key;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Cards added to and removed from a deck section
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SectionDiff {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// cards added, count is how many copies were added
+    pub added: Vec<Card>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// cards removed, count is how many copies were removed
+    pub removed: Vec<Card>,
+}
+
+impl SectionDiff {
+    /// Whether the section is unchanged
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    fn new(from: &[Card], to: &[Card]) -> Self {
+        let mut counts: BTreeMap<(String, u8), i32> = BTreeMap::new();
+        for (cards, sign) in [(from, -1), (to, 1)] {
+            for card in cards {
+                *counts
+                    .entry((copy_key(&card.number), card.parallel_id))
+                    .or_insert(0) += sign * i32::from(card.count);
+            }
+        }
+        let mut section_diff = SectionDiff::default();
+        for ((number, parallel_id), count) in counts {
+            let cards = if count > 0 {
+                &mut section_diff.added
+            } else {
+                &mut section_diff.removed
+            };
+            // counts above 255 are split into further cards like
+            // Deck::canonicalize does
+            let mut count = count.unsigned_abs();
+            while count > 0 {
+                let card_count = count.min(u8::MAX.into());
+                cards.push(Card {
+                    number: number.clone(),
+                    parallel_id,
+                    count: card_count as u8,
+                });
+                count -= card_count;
+            }
+        }
+        section_diff
+    }
+}
+
+/// A value that differs between two decks
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Change<T> {
+    /// value in the original deck
+    pub from: T,
+    /// value in the updated deck
+    pub to: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn new(from: T, to: T) -> Option<Self> {
+        if from == to {
+            None
+        } else {
+            Some(Change { from, to })
+        }
+    }
+}
+
+/// Differences between two decks
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeckDiff {
+    #[serde(
+        rename(serialize = "digi-eggs"),
+        skip_serializing_if = "SectionDiff::is_empty"
+    )]
+    /// changes to digi-egg deck
+    pub digi_eggs: SectionDiff,
+    #[serde(skip_serializing_if = "SectionDiff::is_empty")]
+    /// changes to main deck
+    pub deck: SectionDiff,
+    #[serde(skip_serializing_if = "SectionDiff::is_empty")]
+    /// changes to sideboard
+    pub sideboard: SectionDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// changed deck name
+    pub name: Option<Change<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// changed deck icon
+    pub icon: Option<Change<Option<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// changed deck language
+    pub language: Option<Change<Option<Language>>>,
+}
+
+impl DeckDiff {
+    /// Whether both decks are the same
+    pub fn is_empty(&self) -> bool {
+        self.digi_eggs.is_empty()
+            && self.deck.is_empty()
+            && self.sideboard.is_empty()
+            && self.name.is_none()
+            && self.icon.is_none()
+            && self.language.is_none()
+    }
+}
+
+impl Deck {
+    /// Differences from this deck to `other`
+    ///
+    /// Cards are compared by card number regardless of case and zero
+    /// padding, with the counts of duplicate entries summed, so neither
+    /// shows up as changes.
+    pub fn diff(&self, other: &Deck) -> DeckDiff {
+        DeckDiff {
+            digi_eggs: SectionDiff::new(&self.digi_eggs, &other.digi_eggs),
+            deck: SectionDiff::new(&self.deck, &other.deck),
+            sideboard: SectionDiff::new(&self.sideboard, &other.sideboard),
+            name: Change::new(self.name.clone(), other.name.clone()),
+            icon: Change::new(self.icon.clone(), other.icon.clone()),
+            language: Change::new(
+                self.language.clone(),
+                other.language.clone(),
+            ),
+        }
+    }
+}
+
+fn fmt_section(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    section_diff: &SectionDiff,
+) -> fmt::Result {
+    if section_diff.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{}", title)?;
+    for (sign, cards) in
+        [('+', &section_diff.added), ('-', &section_diff.removed)]
+    {
+        for card in cards {
+            write!(f, "{} {} {}", sign, card.count, card.number)?;
+            if card.parallel_id > 0 {
+                write!(f, " (P{})", card.parallel_id)?;
+            }
+            writeln!(f)?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for DeckDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        fmt_section(f, "Digi-Egg", &self.digi_eggs)?;
+        fmt_section(f, "Main", &self.deck)?;
+        fmt_section(f, "Sideboard", &self.sideboard)?;
+        if let Some(Change { from, to }) = &self.name {
+            writeln!(f, "Name: {:?} -> {:?}", from, to)?;
+        }
+        if let Some(Change { from, to }) = &self.icon {
+            writeln!(f, "Icon: {:?} -> {:?}", from, to)?;
+        }
+        if let Some(Change { from, to }) = &self.language {
+            let tag = |language: &Option<Language>| {
+                language
+                    .as_ref()
+                    .map_or_else(|| "none".to_string(), Language::to_string)
+            };
+            writeln!(f, "Language: {} -> {}", tag(from), tag(to))?;
+        }
+        Ok(())
+    }
+}
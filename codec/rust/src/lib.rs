@@ -1 +1,11 @@
+pub mod carddb;
 pub mod codec;
+pub mod diff;
+pub mod limitation;
+pub mod normalize;
+pub mod probability;
+pub mod qr;
+pub mod stats;
+pub mod text;
+pub mod tts;
+pub mod validate;
@@ -0,0 +1,364 @@
+//! Banned and restricted card lists
+
+use crate::codec::Deck;
+use crate::normalize::copy_key;
+use crate::validate::{copies, Violation};
+use edn_rs::Edn;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{BTreeSet, HashMap};
+use std::{fmt, fs, path::Path, str::FromStr};
+
+/// Error returned when a date isn't written as YYYY-MM-DD
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDateError {
+    /// date as written
+    pub date: String,
+}
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid date '{}', expected YYYY-MM-DD", self.date)
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+/// A calendar day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// year
+    pub year: u16,
+    /// month, 1-12
+    pub month: u8,
+    /// day of the month, 1-31
+    pub day: u8,
+}
+
+/// Days of a month, 29 for February of leap years
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4)
+            && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+        {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    /// Parse "YYYY-MM-DD", or the date of an ISO 8601 timestamp such as
+    /// "2022-05-13T04:00:00.000-00:00"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDateError {
+            date: s.to_string(),
+        };
+        let date = s.split('T').next().unwrap_or(s);
+        let mut parts = date.splitn(3, '-');
+        let mut part = |len: usize| {
+            parts
+                .next()
+                .filter(|part| {
+                    part.len() == len
+                        && part.bytes().all(|b| b.is_ascii_digit())
+                })
+                .and_then(|part| part.parse::<u16>().ok())
+                .ok_or_else(error)
+        };
+        let (year, month, day) = (part(4)?, part(2)?, part(2)?);
+        if !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month as u8)).contains(&(day as u8))
+        {
+            return Err(error());
+        }
+        Ok(Date {
+            year,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Kind of limitation announced for a card
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LimitationType {
+    /// card can't be played
+    Ban,
+    /// card can only be played up to its allowance
+    Restrict,
+    /// earlier limitations of the card are lifted
+    Unrestrict,
+    /// card can't be played with its paired card numbers
+    BannedPair,
+}
+
+/// A ban, restriction, pair ban or lifted limitation of a card number
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Limitation {
+    #[serde(rename = "type")]
+    /// kind of limitation
+    pub limitation_type: LimitationType,
+    #[serde(default)]
+    /// date the limitation takes effect
+    pub date: Option<Date>,
+    #[serde(default)]
+    /// copies allowed, 0 for bans and pair bans
+    pub allowance: Option<u32>,
+    #[serde(default, rename = "paired-card-numbers")]
+    /// card numbers a banned pair card can't be played with
+    pub paired_card_numbers: Vec<String>,
+}
+
+/// Error returned when a limitation list can't be loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadLimitationsError {
+    /// file could not be read
+    Io(String),
+    /// file is not a JSON or EDN limitation list
+    Parse(String),
+}
+
+impl fmt::Display for LoadLimitationsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadLimitationsError::Io(message) => {
+                write!(f, "Failed to read limitation list: {}", message)
+            }
+            LoadLimitationsError::Parse(message) => {
+                write!(f, "Invalid limitation list: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadLimitationsError {}
+
+/// Name of an EDN map key the way clojure.data.json writes it, dropping the
+/// keyword namespace (i.e. ":limitation/date" is "date")
+fn edn_key_name(key: &str) -> String {
+    if let Some(keyword) = key.strip_prefix(':') {
+        keyword.rsplit('/').next().unwrap_or(keyword).to_string()
+    } else {
+        serde_json::from_str(key).unwrap_or_else(|_| key.to_string())
+    }
+}
+
+/// JSON value of an EDN value, with tagged values such as #inst read as
+/// their untagged value
+fn edn_to_json(edn: Edn) -> serde_json::Value {
+    use serde_json::Value;
+    match edn {
+        Edn::Tagged(_, edn) => edn_to_json(*edn),
+        Edn::Vector(vector) => {
+            Value::Array(vector.to_vec().into_iter().map(edn_to_json).collect())
+        }
+        Edn::List(list) => {
+            Value::Array(list.to_vec().into_iter().map(edn_to_json).collect())
+        }
+        Edn::Set(set) => {
+            Value::Array(set.to_set().into_iter().map(edn_to_json).collect())
+        }
+        Edn::Map(map) => Value::Object(
+            map.to_map()
+                .into_iter()
+                .map(|(key, edn)| (edn_key_name(&key), edn_to_json(edn)))
+                .collect(),
+        ),
+        Edn::Key(key) => Value::String(edn_key_name(&key)),
+        Edn::Symbol(s) | Edn::Str(s) | Edn::Rational(s) => Value::String(s),
+        Edn::Int(n) => n.into(),
+        Edn::UInt(n) => n.into(),
+        Edn::Double(n) => n.to_string().parse::<f64>().unwrap_or(0.0).into(),
+        Edn::Char(c) => Value::String(c.to_string()),
+        Edn::Bool(b) => Value::Bool(b),
+        // nil, and values added by later edn-rs versions
+        _ => Value::Null,
+    }
+}
+
+/// Limitations of every card number as exported by `dcg.db.card.limitation`
+///
+/// The export maps each card number to its limitations by language tag,
+/// where the "default" language holds the English limitations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LimitationList {
+    limitations: HashMap<String, HashMap<String, Vec<Limitation>>>,
+}
+
+impl LimitationList {
+    /// Load an exported limitation list, read as EDN for files ending in
+    /// ".edn" and as JSON otherwise
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<LimitationList, LoadLimitationsError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| LoadLimitationsError::Io(e.to_string()))?;
+        if path.extension().is_some_and(|extension| extension == "edn") {
+            LimitationList::from_edn(&text)
+        } else {
+            LimitationList::from_json(&text)
+        }
+    }
+
+    /// Read a limitation list from JSON
+    pub fn from_json(
+        json: &str,
+    ) -> Result<LimitationList, LoadLimitationsError> {
+        let value = serde_json::from_str(json)
+            .map_err(|e| LoadLimitationsError::Parse(e.to_string()))?;
+        LimitationList::from_value(value)
+    }
+
+    /// Read a limitation list from EDN
+    pub fn from_edn(edn: &str) -> Result<LimitationList, LoadLimitationsError> {
+        let edn = Edn::from_str(edn)
+            .map_err(|e| LoadLimitationsError::Parse(format!("{:?}", e)))?;
+        LimitationList::from_value(edn_to_json(edn))
+    }
+
+    fn from_value(
+        value: serde_json::Value,
+    ) -> Result<LimitationList, LoadLimitationsError> {
+        let export: HashMap<String, HashMap<String, Vec<Limitation>>> =
+            serde_json::from_value(value)
+                .map_err(|e| LoadLimitationsError::Parse(e.to_string()))?;
+        let mut limitation_list = LimitationList::default();
+        for (number, limitations) in export {
+            limitation_list
+                .limitations
+                .entry(copy_key(&number))
+                .or_default()
+                .extend(limitations);
+        }
+        Ok(limitation_list)
+    }
+
+    /// Limitations of a card number for a language tag, falling back to the
+    /// default limitations, in the order they take effect
+    fn card_limitations(
+        &self,
+        number: &str,
+        language: &str,
+    ) -> Vec<&Limitation> {
+        let mut limitations: Vec<&Limitation> = self
+            .limitations
+            .get(&copy_key(number))
+            .and_then(|by_language| {
+                by_language
+                    .get(language)
+                    .or_else(|| by_language.get("default"))
+                    .or_else(|| by_language.get("en"))
+            })
+            .map(|limitations| limitations.iter().collect())
+            .unwrap_or_default();
+        limitations.sort_by_key(|limitation| limitation.date);
+        limitations
+    }
+
+    /// Copies allowed and banned pair card numbers of a card number on
+    /// `date`, by the latest limitations in effect
+    ///
+    /// Limitations without a date are ignored.
+    pub fn limit(
+        &self,
+        number: &str,
+        language: &str,
+        date: &Date,
+    ) -> (Option<u32>, Vec<String>) {
+        let mut allowance = None;
+        let mut paired_card_numbers = vec![];
+        for limitation in self.card_limitations(number, language) {
+            if limitation.date.is_none_or(|d| d > *date) {
+                continue;
+            }
+            match limitation.limitation_type {
+                LimitationType::Ban => allowance = Some(0),
+                LimitationType::Restrict => {
+                    allowance = Some(limitation.allowance.unwrap_or(1))
+                }
+                LimitationType::Unrestrict => {
+                    allowance = None;
+                    paired_card_numbers.clear();
+                }
+                LimitationType::BannedPair => {
+                    paired_card_numbers = limitation.paired_card_numbers.clone()
+                }
+            }
+        }
+        (allowance, paired_card_numbers)
+    }
+
+    /// Banned cards, restricted cards over their allowance and banned pairs
+    /// of a deck on `date`
+    ///
+    /// Limitations of the deck language are used when the list has them,
+    /// otherwise the default (English) limitations.
+    pub fn check(&self, deck: &Deck, date: &Date) -> Vec<Violation> {
+        let language = deck
+            .language
+            .as_ref()
+            .map(|language| language.to_string())
+            .unwrap_or_else(|| "default".to_string());
+        let copies = copies(deck);
+        let mut violations = vec![];
+        let mut banned_pairs = BTreeSet::new();
+        for (number, &count) in &copies {
+            let (allowance, paired_card_numbers) =
+                self.limit(number, &language, date);
+            match allowance {
+                Some(0) => violations.push(Violation::Banned {
+                    number: number.clone(),
+                    count,
+                }),
+                Some(max) if count > max => {
+                    violations.push(Violation::Restricted {
+                        number: number.clone(),
+                        count,
+                        max,
+                    })
+                }
+                _ => {}
+            }
+            for paired_number in paired_card_numbers {
+                let paired_number = copy_key(&paired_number);
+                if copies.contains_key(&paired_number) {
+                    banned_pairs.insert(if *number < paired_number {
+                        (number.clone(), paired_number)
+                    } else {
+                        (paired_number, number.clone())
+                    });
+                }
+            }
+        }
+        violations.extend(banned_pairs.into_iter().map(
+            |(number, paired_number)| Violation::BannedPair {
+                number,
+                paired_number,
+            },
+        ));
+        violations
+    }
+}
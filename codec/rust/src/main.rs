@@ -2,34 +2,370 @@
 
 #![deny(missing_docs)]
 
-mod codec;
-
-pub use crate::codec::{decode, encode, Deck, VERSION};
-use serde_json;
+use dcg_codec::carddb::CardDb;
+use dcg_codec::codec::{
+    self, Deck, EncodeError, Language, LossWarning, VERSION,
+};
+use dcg_codec::limitation::{Date, LimitationList};
+use dcg_codec::normalize::PaddingRules;
+use dcg_codec::qr::{self, EcLevel, QrOptions};
+use dcg_codec::stats::DeckStats;
+use dcg_codec::tts::TtsOptions;
+use dcg_codec::validate::RuleSet;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 
+#[derive(StructOpt)]
+enum Command {
+    /// Show the cards, name, icon and language changed between two decks
+    Diff {
+        /// Original deck code
+        deck_code_a: String,
+        /// Updated deck code
+        deck_code_b: String,
+        /// Print the changes as JSON
+        #[structopt(long = "json")]
+        json: bool,
+    },
+    /// Export a deck code as a Tabletop Simulator saved object
+    Tts {
+        /// Deck code
+        deck_code: String,
+        /// Card face URL template with {language}, {number}, {parallel_id},
+        /// {id} (en_BT5-012_P1) and {image} (BT5-012_P1) placeholders
+        #[structopt(long = "face-url")]
+        face_url_template: String,
+        /// Card back URL
+        #[structopt(long = "back-url")]
+        back_url: String,
+        /// Card face language of decks without a language
+        #[structopt(long = "language", default_value = "en")]
+        language: Language,
+        /// Write the saved object to a file instead of stdout
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Render a deck code as a QR code
+    Qr {
+        /// Deck code
+        deck_code: String,
+        /// Image format
+        #[structopt(
+            long = "format",
+            default_value = "svg",
+            possible_values = &["svg", "png"]
+        )]
+        format: String,
+        /// Error correction level (L, M, Q or H)
+        #[structopt(long = "ec-level", default_value = "M")]
+        ec_level: EcLevel,
+        /// Width and height of a QR code module in pixels, up to 64
+        #[structopt(long = "module-size", default_value = "8")]
+        module_size: u32,
+        /// Print the deck name under the QR code
+        #[structopt(long = "caption")]
+        caption: bool,
+        /// Write the image to a file instead of stdout
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Decode the deck code of a QR code in a PNG or JPEG image
+    ScanQr {
+        /// PNG or JPEG image
+        #[structopt(parse(from_os_str))]
+        image: PathBuf,
+    },
+    /// Check a deck code against the deck construction rules, exiting with
+    /// status 1 on violations
+    Validate {
+        /// Deck code
+        deck_code: String,
+        /// Print the violations as JSON
+        #[structopt(long = "json")]
+        json: bool,
+        /// Check banned and restricted cards of an exported JSON or EDN
+        /// limitation list
+        #[structopt(
+            long = "limitations",
+            requires = "as-of",
+            parse(from_os_str)
+        )]
+        limitations: Option<PathBuf>,
+        /// Date (YYYY-MM-DD) the limitation list is checked on
+        #[structopt(long = "as-of", requires = "limitations")]
+        as_of: Option<Date>,
+    },
+    /// Show the color, level, cost, category and trait counts of a deck
+    Stats {
+        /// Deck code
+        deck_code: String,
+        /// api bulk data export with the card data
+        #[structopt(long = "card-db", parse(from_os_str))]
+        card_db: PathBuf,
+        /// Print the statistics as JSON
+        #[structopt(long = "json")]
+        json: bool,
+    },
+}
+
 /// Digimon Card Game 2020 deck codec
 #[derive(StructOpt)]
 struct Cli {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
     #[structopt(long = "decode", conflicts_with = "deck")]
     deck_code_str: Option<String>,
 
+    /// Add card names and data from an api bulk data export to decoded
+    /// decks, in English for decks without a language
+    #[structopt(
+        long = "card-db",
+        requires = "deck-code-str",
+        parse(from_os_str)
+    )]
+    card_db: Option<PathBuf>,
+
     #[structopt(long = "encode", conflicts_with = "deck-code-str")]
     deck: Option<String>,
+
+    /// Encode a plain-text deck list
+    #[structopt(
+        long = "from-text",
+        conflicts_with_all = &["deck", "deck-code-str"]
+    )]
+    deck_text: Option<String>,
+
+    /// Decode a deck code to a plain-text deck list
+    #[structopt(
+        long = "to-text",
+        conflicts_with_all = &["deck", "deck-code-str", "deck-text"]
+    )]
+    text_deck_code_str: Option<String>,
+
+    /// Merge duplicate cards and normalize card numbers before encoding
+    #[structopt(long = "canonicalize")]
+    canonicalize: bool,
+
+    /// Fix card number case, separators and zero padding before encoding
+    #[structopt(long = "normalize")]
+    normalize: bool,
+
+    /// Language of encoded decks without one, which deck codes store since
+    /// v3
+    #[structopt(long = "language")]
+    language: Option<Language>,
+
+    /// Zero padding by card set prefix as JSON (i.e. {"BT": 3, "ST": 2})
+    /// used by --normalize instead of the released card sets
+    #[structopt(long = "padding-rules", requires = "normalize")]
+    padding_rules: Option<String>,
+}
+
+/// Decode a deck code or exit with the decode error
+fn decode_or_exit(deck_code_str: &str) -> Deck {
+    codec::try_decode(deck_code_str).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+/// Load a card database or exit with the load error
+fn load_card_db_or_exit(path: &Path) -> CardDb {
+    CardDb::load(path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+/// Write to a file, or stdout without a path, or exit with the write error
+fn write_or_exit(path: &Option<PathBuf>, bytes: &[u8]) {
+    let result = match path {
+        Some(path) => fs::write(path, bytes),
+        None => io::stdout().write_all(bytes),
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
 }
 
 fn main() {
     let args = Cli::from_args();
 
+    match &args.command {
+        Some(Command::Diff {
+            deck_code_a,
+            deck_code_b,
+            json,
+        }) => {
+            let deck_diff =
+                decode_or_exit(deck_code_a).diff(&decode_or_exit(deck_code_b));
+            if *json {
+                println!("{}", serde_json::to_string(&deck_diff).unwrap());
+            } else {
+                print!("{}", deck_diff);
+            }
+        }
+        Some(Command::Tts {
+            deck_code,
+            face_url_template,
+            back_url,
+            language,
+            output,
+        }) => {
+            let tts_save = decode_or_exit(deck_code).to_tts(&TtsOptions {
+                face_url_template: face_url_template.clone(),
+                back_url: back_url.clone(),
+                language: language.clone(),
+            });
+            let json = serde_json::to_string_pretty(&tts_save).unwrap() + "\n";
+            write_or_exit(output, json.as_bytes());
+        }
+        Some(Command::Qr {
+            deck_code,
+            format,
+            ec_level,
+            module_size,
+            caption,
+            output,
+        }) => {
+            let deck = decode_or_exit(deck_code);
+            let options = QrOptions {
+                ec_level: *ec_level,
+                module_size: *module_size,
+                caption: if *caption { Some(deck.name) } else { None },
+            };
+            let image = if format == "png" {
+                qr::render_png(deck_code, &options)
+            } else {
+                qr::render_svg(deck_code, &options).map(String::into_bytes)
+            }
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            write_or_exit(output, &image);
+        }
+        Some(Command::ScanQr { image }) => {
+            let deck_code = fs::read(image)
+                .map_err(|e| e.to_string())
+                .and_then(|image| qr::scan(&image).map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+            let deck = decode_or_exit(&deck_code);
+            println!("{}", serde_json::to_string(&deck).unwrap());
+        }
+        Some(Command::Validate {
+            deck_code,
+            json,
+            limitations,
+            as_of,
+        }) => {
+            let deck = decode_or_exit(deck_code);
+            let mut violations = deck.validate(&RuleSet::default());
+            if let (Some(limitations), Some(as_of)) = (limitations, as_of) {
+                let limitation_list = LimitationList::load(limitations)
+                    .unwrap_or_else(|e| {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    });
+                violations.extend(limitation_list.check(&deck, as_of));
+            }
+            if *json {
+                println!("{}", serde_json::to_string(&violations).unwrap());
+            } else if violations.is_empty() {
+                println!("Valid");
+            } else {
+                for violation in &violations {
+                    println!("{}", violation);
+                }
+            }
+            if !violations.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Stats {
+            deck_code,
+            card_db,
+            json,
+        }) => {
+            let deck = decode_or_exit(deck_code);
+            let stats =
+                DeckStats::compute(&deck, &load_card_db_or_exit(card_db));
+            if *json {
+                println!("{}", serde_json::to_string(&stats).unwrap());
+            } else {
+                print!("{}", stats);
+            }
+        }
+        None => {}
+    }
+
     if let Some(deck_code_str) = &args.deck_code_str {
-        println!(
-            "{}",
-            serde_json::to_string(&codec::decode(deck_code_str)).unwrap()
-        );
+        let deck = decode_or_exit(deck_code_str);
+        if let Some(card_db) = &args.card_db {
+            let card_db = load_card_db_or_exit(card_db);
+            let enriched_deck = deck.enrich(&card_db, &Language::English);
+            println!("{}", serde_json::to_string(&enriched_deck).unwrap());
+        } else {
+            println!("{}", serde_json::to_string(&deck).unwrap());
+        }
+    }
+
+    if let Some(deck_code_str) = &args.text_deck_code_str {
+        print!("{}", decode_or_exit(deck_code_str).to_text());
     }
 
-    if let Some(deck_str) = &args.deck {
-        let deck: Deck = serde_json::from_str(deck_str).unwrap();
-        println!("{:?}", codec::encode(deck, VERSION));
+    let deck = args
+        .deck
+        .as_ref()
+        .map(|deck_str| serde_json::from_str::<Deck>(deck_str).unwrap())
+        .or_else(|| {
+            args.deck_text.as_ref().map(|deck_text| {
+                Deck::from_text(deck_text).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                })
+            })
+        });
+    if let Some(mut deck) = deck {
+        if args.normalize {
+            let padding_rules = match &args.padding_rules {
+                Some(padding_rules) => serde_json::from_str(padding_rules)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Invalid padding rules: {}", e);
+                        std::process::exit(1);
+                    }),
+                None => PaddingRules::default(),
+            };
+            deck.normalize(&padding_rules);
+        }
+        if deck.language.is_none() {
+            deck.language = args.language.clone();
+        }
+        let deck_code = if args.canonicalize {
+            codec::encode_canonical(&deck, VERSION)
+        } else {
+            codec::try_encode(deck, VERSION)
+        };
+        match deck_code {
+            Ok(deck_code) => println!("{:?}", deck_code),
+            Err(e) => {
+                eprintln!("{}", e);
+                if let EncodeError::Lossy { warnings, .. } = &e {
+                    if warnings.contains(&LossWarning::UnspecifiedLanguage) {
+                        eprintln!("Pass --language to set the deck language");
+                    }
+                }
+                std::process::exit(1);
+            }
+        }
     }
 }
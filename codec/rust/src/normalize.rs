@@ -0,0 +1,141 @@
+//! Card number normalization
+
+use crate::codec::{CardNumber, Deck, ParseCardNumberError};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Zero padding of card numbers by card set prefix (i.e. "ST" for "ST1")
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(from = "HashMap<String, usize>")]
+pub struct PaddingRules {
+    paddings: HashMap<String, usize>,
+}
+
+impl Default for PaddingRules {
+    /// Paddings of the released card sets
+    fn default() -> Self {
+        let mut padding_rules = PaddingRules::new();
+        for (card_set_prefix, padding) in [
+            ("BT", 3),
+            ("EX", 3),
+            ("LM", 3),
+            ("P", 3),
+            ("RB", 3),
+            ("ST", 2),
+        ] {
+            padding_rules.set_padding(card_set_prefix, padding);
+        }
+        padding_rules
+    }
+}
+
+/// Paddings of the released card sets, built once and shared
+pub(crate) fn default_padding_rules() -> &'static PaddingRules {
+    static PADDING_RULES: OnceLock<PaddingRules> = OnceLock::new();
+    PADDING_RULES.get_or_init(PaddingRules::default)
+}
+
+/// Card number copies are counted and looked up by, so padding and case
+/// don't matter
+pub(crate) fn copy_key(number: &str) -> String {
+    default_padding_rules()
+        .normalize(number)
+        .map(|card_number| card_number.to_string())
+        .unwrap_or_else(|_| number.to_string())
+}
+
+impl From<HashMap<String, usize>> for PaddingRules {
+    fn from(paddings: HashMap<String, usize>) -> Self {
+        let mut padding_rules = PaddingRules::new();
+        for (card_set_prefix, padding) in paddings {
+            padding_rules.set_padding(&card_set_prefix, padding);
+        }
+        padding_rules
+    }
+}
+
+/// Split a card set into its letters and number (i.e. "BT" and "01")
+fn split_card_set(card_set: &str) -> (&str, &str) {
+    let letters = card_set
+        .bytes()
+        .take_while(|b| b.is_ascii_alphabetic())
+        .count();
+    card_set.split_at(letters)
+}
+
+impl PaddingRules {
+    /// Rules without any card set, which keep the padding as typed
+    pub fn new() -> Self {
+        PaddingRules {
+            paddings: HashMap::new(),
+        }
+    }
+
+    /// Set the zero padding of every card set starting with
+    /// `card_set_prefix`
+    pub fn set_padding(&mut self, card_set_prefix: &str, padding: usize) {
+        self.paddings
+            .insert(card_set_prefix.to_uppercase(), padding);
+    }
+
+    /// Zero padding of a card set, if it has a rule
+    pub fn padding(&self, card_set: &str) -> Option<usize> {
+        let (card_set_prefix, _) = split_card_set(card_set);
+        self.paddings.get(&card_set_prefix.to_uppercase()).copied()
+    }
+
+    /// Normalize a typed card number (i.e. "bt1-1", "BT01-001" or "st1_01")
+    ///
+    /// The card set is upper cased and stripped of zero padding, an
+    /// underscore or space before the number is read as a hyphen, and the
+    /// number is zero padded by the rule of its card set. Numbers too wide
+    /// for the rule keep their digits.
+    pub fn normalize(
+        &self,
+        number: &str,
+    ) -> Result<CardNumber, ParseCardNumberError> {
+        let typed_number = number.trim().to_uppercase();
+        let typed_number = match typed_number.rfind(['_', ' ']) {
+            Some(i) if !typed_number.contains('-') => {
+                format!("{}-{}", &typed_number[..i], &typed_number[i + 1..])
+            }
+            _ => typed_number,
+        };
+        let mut card_number: CardNumber =
+            typed_number.parse().map_err(|_| ParseCardNumberError {
+                number: number.to_string(),
+            })?;
+        let (letters, digits) = split_card_set(&card_number.card_set);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            let digits = digits.trim_start_matches('0');
+            card_number.card_set = format!(
+                "{}{}",
+                letters,
+                if digits.is_empty() { "0" } else { digits }
+            );
+        }
+        if let Some(padding) = self.padding(&card_number.card_set) {
+            let width = card_number.number.to_string().len();
+            card_number.padding = padding.max(width);
+        }
+        Ok(card_number)
+    }
+}
+
+impl Deck {
+    /// Normalize every card number of the deck by `padding_rules`, leaving
+    /// card numbers that can't be parsed as they are
+    pub fn normalize(&mut self, padding_rules: &PaddingRules) {
+        for card in self
+            .digi_eggs
+            .iter_mut()
+            .chain(&mut self.deck)
+            .chain(&mut self.sideboard)
+        {
+            if let Ok(card_number) = padding_rules.normalize(&card.number) {
+                card.number = card_number.to_string();
+            }
+        }
+    }
+}
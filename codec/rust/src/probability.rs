@@ -0,0 +1,194 @@
+//! Exact draw probabilities of main deck cards
+
+use crate::codec::Deck;
+use crate::normalize::copy_key;
+
+/// Cards drawn into the opening hand
+pub const OPENING_HAND_SIZE: u32 = 5;
+/// Cards placed face down as security cards after drawing the opening hand
+pub const SECURITY_SIZE: u32 = 5;
+
+/// Whether a player takes the first turn of the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TurnOrder {
+    /// going first, which skips the draw of the first turn
+    First,
+    /// going second, which draws on every turn
+    Second,
+}
+
+/// Ways to pick `k` of `n` items, or None when they don't fit in a u128
+fn binomial(n: u32, k: u32) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    // every partial product is itself a binomial, so the division is exact
+    (0..k).try_fold(1, |c: u128, i| {
+        Some(c.checked_mul(u128::from(n - i))? / u128::from(i + 1))
+    })
+}
+
+/// Natural logarithm of the ways to pick `k` of `n` items, `k <= n`
+fn ln_binomial(n: u32, k: u32) -> f64 {
+    let k = k.min(n - k);
+    (0..k)
+        .map(|i| f64::from(n - i).ln() - f64::from(i + 1).ln())
+        .sum()
+}
+
+/// Ways to draw exactly `count` successes, or None when they don't fit in
+/// a u128
+fn ways(
+    population: u32,
+    successes: u32,
+    sample: u32,
+    count: u32,
+) -> Option<u128> {
+    binomial(successes, count)?
+        .checked_mul(binomial(population - successes, sample - count)?)
+}
+
+/// Probability of exactly `count` successes computed in log space, for
+/// populations too large to count the ways exactly
+fn ln_exactly(population: u32, successes: u32, sample: u32, count: u32) -> f64 {
+    if sample - count > population - successes {
+        return 0.0;
+    }
+    (ln_binomial(successes, count)
+        + ln_binomial(population - successes, sample - count)
+        - ln_binomial(population, sample))
+    .exp()
+}
+
+pub fn exactly(
+    population: u32,
+    successes: u32,
+    sample: u32,
+    count: u32,
+) -> f64 {
+    //! Hypergeometric probability of exactly `count` successes in a sample
+    //! of `sample` cards from `population` cards with `successes` successes
+    //!
+    //! The ways are counted exactly unless they don't fit in a u128, in
+    //! which case the probability is computed in log space.
+    let sample = sample.min(population);
+    let successes = successes.min(population);
+    if count > sample || count > successes {
+        return 0.0;
+    }
+    match (
+        ways(population, successes, sample, count),
+        binomial(population, sample),
+    ) {
+        (Some(ways), Some(total)) => ways as f64 / total as f64,
+        _ => ln_exactly(population, successes, sample, count),
+    }
+}
+
+pub fn at_least(
+    population: u32,
+    successes: u32,
+    sample: u32,
+    count: u32,
+) -> f64 {
+    //! Hypergeometric probability of at least `count` successes in a sample
+    //! of `sample` cards from `population` cards with `successes` successes
+    //!
+    //! The ways to draw every success count are summed exactly before the
+    //! one division, unless they don't fit in a u128, in which case the
+    //! probabilities are computed in log space and summed.
+    let sample = sample.min(population);
+    let successes = successes.min(population);
+    if count == 0 {
+        return 1.0;
+    }
+    let counts = count..=sample.min(successes);
+    let exact = counts
+        .clone()
+        .try_fold(0, |sum: u128, i| {
+            sum.checked_add(ways(population, successes, sample, i)?)
+        })
+        .zip(binomial(population, sample));
+    match exact {
+        Some((ways, total)) => ways as f64 / total as f64,
+        None => counts
+            .map(|i| ln_exactly(population, successes, sample, i))
+            .sum::<f64>()
+            .min(1.0),
+    }
+}
+
+pub fn cards_seen(turn: u32, turn_order: TurnOrder) -> u32 {
+    //! Cards in the opening hand and drawn by the draw phase of the
+    //! player's `turn`, starting at turn 1
+    //!
+    //! Security cards are placed from the deck after the opening hand, but
+    //! the cards drawn are still a random pick of the main deck.
+    let draws = match turn_order {
+        TurnOrder::First => turn.saturating_sub(1),
+        TurnOrder::Second => turn,
+    };
+    OPENING_HAND_SIZE + draws
+}
+
+impl Deck {
+    /// Count of main deck cards
+    pub fn main_deck_size(&self) -> u32 {
+        self.deck.iter().map(|card| u32::from(card.count)).sum()
+    }
+
+    /// Copies in the main deck of any card number of a group, across
+    /// parallel ids and regardless of card number case and zero padding
+    pub fn group_count(&self, numbers: &[&str]) -> u32 {
+        let numbers: Vec<String> =
+            numbers.iter().map(|number| copy_key(number)).collect();
+        self.deck
+            .iter()
+            .filter(|card| numbers.contains(&copy_key(&card.number)))
+            .map(|card| u32::from(card.count))
+            .sum()
+    }
+
+    /// Probability of at least `count` cards of a group in the opening hand
+    pub fn opening_hand_probability(
+        &self,
+        numbers: &[&str],
+        count: u32,
+    ) -> f64 {
+        at_least(
+            self.main_deck_size(),
+            self.group_count(numbers),
+            OPENING_HAND_SIZE,
+            count,
+        )
+    }
+
+    /// Probability of at least `count` cards of a group in the opening hand
+    /// or drawn by the draw phase of the player's `turn`
+    pub fn by_turn_probability(
+        &self,
+        numbers: &[&str],
+        count: u32,
+        turn: u32,
+        turn_order: TurnOrder,
+    ) -> f64 {
+        at_least(
+            self.main_deck_size(),
+            self.group_count(numbers),
+            cards_seen(turn, turn_order),
+            count,
+        )
+    }
+
+    /// Probability of at least `count` cards of a group in the security
+    /// cards
+    pub fn security_probability(&self, numbers: &[&str], count: u32) -> f64 {
+        at_least(
+            self.main_deck_size(),
+            self.group_count(numbers),
+            SECURITY_SIZE,
+            count,
+        )
+    }
+}
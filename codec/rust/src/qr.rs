@@ -0,0 +1,431 @@
+//! QR codes of deck codes
+
+use crate::codec::PREFIX;
+use font8x8::{UnicodeFonts, BASIC_FONTS, HIRAGANA_FONTS, LATIN_FONTS};
+use qrcode::{Color, QrCode};
+use std::{fmt, str::FromStr};
+
+/// Errors that can occur while rendering a QR code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QrError {
+    /// deck code is too long for a QR code at the error correction level
+    DataTooLong,
+    /// module size is larger than [`MAX_MODULE_SIZE`]
+    ModuleSizeTooLarge(u32),
+    /// PNG caption has a character the bitmap font can't draw
+    CaptionCharacter(char),
+    /// PNG image could not be written
+    Png,
+}
+
+impl fmt::Display for QrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QrError::DataTooLong => {
+                write!(f, "Deck code is too long for a QR code")
+            }
+            QrError::ModuleSizeTooLarge(module_size) => write!(
+                f,
+                "Module size {} is larger than {} pixels",
+                module_size, MAX_MODULE_SIZE
+            ),
+            QrError::CaptionCharacter(chr) => write!(
+                f,
+                "Caption character '{}' can't be drawn in a PNG image",
+                chr
+            ),
+            QrError::Png => write!(f, "Failed to write PNG image"),
+        }
+    }
+}
+
+impl std::error::Error for QrError {}
+
+/// Errors that can occur while scanning a QR code image
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScanQrError {
+    /// image is not a PNG or JPEG image that can be read
+    Image,
+    /// no QR code could be read in the image
+    NotFound,
+    /// QR code does not start with PREFIX
+    NotDeckCode {
+        /// text of the QR code
+        payload: String,
+    },
+}
+
+impl fmt::Display for ScanQrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanQrError::Image => {
+                write!(f, "Image is not a readable PNG or JPEG image")
+            }
+            ScanQrError::NotFound => write!(f, "No QR code found in image"),
+            ScanQrError::NotDeckCode { payload } => write!(
+                f,
+                "QR code '{}' is not a deck code starting with '{}'",
+                payload, PREFIX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScanQrError {}
+
+/// QR code error correction level, from the least to the most damage a
+/// QR code can take and still be read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EcLevel {
+    /// 7% of the QR code can be restored
+    Low,
+    /// 15% of the QR code can be restored
+    Medium,
+    /// 25% of the QR code can be restored
+    Quartile,
+    /// 30% of the QR code can be restored
+    High,
+}
+
+impl FromStr for EcLevel {
+    type Err = String;
+
+    /// Parse "L", "M", "Q" or "H"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "L" => Ok(EcLevel::Low),
+            "M" => Ok(EcLevel::Medium),
+            "Q" => Ok(EcLevel::Quartile),
+            "H" => Ok(EcLevel::High),
+            _ => Err(format!("Invalid error correction level '{}'", s)),
+        }
+    }
+}
+
+impl From<EcLevel> for qrcode::EcLevel {
+    fn from(ec_level: EcLevel) -> Self {
+        match ec_level {
+            EcLevel::Low => qrcode::EcLevel::L,
+            EcLevel::Medium => qrcode::EcLevel::M,
+            EcLevel::Quartile => qrcode::EcLevel::Q,
+            EcLevel::High => qrcode::EcLevel::H,
+        }
+    }
+}
+
+/// How a QR code is rendered
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrOptions {
+    /// error correction level
+    pub ec_level: EcLevel,
+    /// width and height of a module (one square of the QR code) in pixels
+    pub module_size: u32,
+    /// text printed under the QR code, usually the deck name
+    pub caption: Option<String>,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        QrOptions {
+            ec_level: EcLevel::Medium,
+            module_size: 8,
+            caption: None,
+        }
+    }
+}
+
+/// Largest module size in pixels, which keeps images of the longest deck
+/// codes under a few hundred megapixels
+pub const MAX_MODULE_SIZE: u32 = 64;
+
+/// Module size of rendering options in pixels, at least 1
+fn module_size(options: &QrOptions) -> Result<usize, QrError> {
+    if options.module_size > MAX_MODULE_SIZE {
+        return Err(QrError::ModuleSizeTooLarge(options.module_size));
+    }
+    Ok(options.module_size.max(1) as usize)
+}
+
+/// Modules of blank border around a QR code required by readers
+const QUIET_ZONE: usize = 4;
+
+/// Dark modules of a QR code including its quiet zone, by row
+struct Modules {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+impl Modules {
+    fn new(deck_code: &str, ec_level: EcLevel) -> Result<Self, QrError> {
+        let qr_code =
+            QrCode::with_error_correction_level(deck_code, ec_level.into())
+                .map_err(|_| QrError::DataTooLong)?;
+        let qr_width = qr_code.width();
+        let width = qr_width + 2 * QUIET_ZONE;
+        let mut dark = vec![false; width * width];
+        for (i, color) in qr_code.to_colors().into_iter().enumerate() {
+            let (x, y) = (i % qr_width + QUIET_ZONE, i / qr_width + QUIET_ZONE);
+            dark[y * width + x] = color == Color::Dark;
+        }
+        Ok(Modules { width, dark })
+    }
+
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.width + x]
+    }
+}
+
+/// Font size of SVG captions in modules
+const CAPTION_FONT_SIZE: f32 = 2.5;
+
+/// Estimated width of an SVG caption in modules, with ASCII characters
+/// taking 0.6em of a sans-serif font and other characters a full em
+fn caption_width(caption: &str) -> f32 {
+    caption
+        .chars()
+        .map(|chr| if chr.is_ascii() { 0.6 } else { 1.0 })
+        .sum::<f32>()
+        * CAPTION_FONT_SIZE
+}
+
+/// Escape text for an SVG text element
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_svg(
+    deck_code: &str,
+    options: &QrOptions,
+) -> Result<String, QrError> {
+    //! Render a deck code as a QR code SVG image
+    //!
+    //! Dark modules are drawn as a single path in module units, scaled to
+    //! `module_size` pixels by the image size. Images are widened to fit
+    //! long captions, with a module of space on either side.
+    let module_size = module_size(options)?;
+    let modules = Modules::new(deck_code, options.ec_level)?;
+    let width = modules.width;
+    // caption takes 4 modules under the quiet zone
+    let height = width + if options.caption.is_some() { 4 } else { 0 };
+    let mut path = String::new();
+    for y in 0..width {
+        let mut x = 0;
+        while x < width {
+            if modules.is_dark(x, y) {
+                let start = x;
+                while x < width && modules.is_dark(x, y) {
+                    x += 1;
+                }
+                path += &format!(
+                    "M{},{}h{}v1h-{}z",
+                    start,
+                    y,
+                    x - start,
+                    x - start
+                );
+            } else {
+                x += 1;
+            }
+        }
+    }
+    // modules added on either side of the QR code for the caption
+    let margin = options.caption.as_deref().map_or(0, |caption| {
+        let overflow = caption_width(caption) + 2.0 - width as f32;
+        (overflow / 2.0).ceil().max(0.0) as usize
+    });
+    let image_width = width + 2 * margin;
+    let left = -(margin as isize);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} 0 {} {}\" shape-rendering=\"crispEdges\">\
+         <rect x=\"{}\" width=\"{}\" height=\"{}\" fill=\"#fff\"/>\
+         <path d=\"{}\" fill=\"#000\"/>",
+        image_width * module_size,
+        height * module_size,
+        left,
+        image_width,
+        height,
+        left,
+        image_width,
+        height,
+        path
+    );
+    if let Some(caption) = &options.caption {
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\">{}</text>",
+            width as f32 / 2.0,
+            width + 1,
+            CAPTION_FONT_SIZE,
+            escape_xml(caption)
+        );
+    }
+    svg += "</svg>\n";
+    Ok(svg)
+}
+
+/// 8x8 glyph of a character, where bit 0 of each row is the leftmost pixel
+fn glyph(chr: char) -> Result<[u8; 8], QrError> {
+    BASIC_FONTS
+        .get(chr)
+        .or_else(|| LATIN_FONTS.get(chr))
+        .or_else(|| HIRAGANA_FONTS.get(chr))
+        .ok_or(QrError::CaptionCharacter(chr))
+}
+
+pub fn render_png(
+    deck_code: &str,
+    options: &QrOptions,
+) -> Result<Vec<u8>, QrError> {
+    //! Render a deck code as a QR code grayscale PNG image
+    //!
+    //! Captions are drawn with an 8x8 bitmap font of latin and hiragana
+    //! characters. Captions with other characters, such as katakana and
+    //! kanji, return an error rather than a garbled caption.
+    let module_size = module_size(options)?;
+    let modules = Modules::new(deck_code, options.ec_level)?;
+    let qr_size = modules.width * module_size;
+    let caption: Vec<[u8; 8]> = options
+        .caption
+        .as_deref()
+        .unwrap_or("")
+        .chars()
+        .map(glyph)
+        .collect::<Result<_, _>>()?;
+    // caption pixels are half a module wide, with a module of space below
+    let scale = (module_size / 2).max(1);
+    let caption_width = caption.len() * 8 * scale;
+    let width = qr_size.max(caption_width + 2 * module_size);
+    let height = qr_size
+        + if caption.is_empty() {
+            0
+        } else {
+            8 * scale + module_size
+        };
+
+    let mut pixels = vec![0xFF_u8; width * height];
+    let qr_left = (width - qr_size) / 2;
+    for y in 0..qr_size {
+        for x in 0..qr_size {
+            if modules.is_dark(x / module_size, y / module_size) {
+                pixels[y * width + qr_left + x] = 0x00;
+            }
+        }
+    }
+    let caption_left = (width - caption_width) / 2;
+    for (i, rows) in caption.iter().enumerate() {
+        for (row_index, row) in rows.iter().enumerate() {
+            for bit in 0..8 {
+                if row & 1 << bit == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    let y = qr_size + row_index * scale + dy;
+                    let x = caption_left + (i * 8 + bit) * scale;
+                    pixels[y * width + x..y * width + x + scale].fill(0x00);
+                }
+            }
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    let mut encoder =
+        png::Encoder::new(&mut png_bytes, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|_| QrError::Png)?;
+    Ok(png_bytes)
+}
+
+/// Grayscale value of a gray, gray and alpha, RGB or RGBA pixel, with
+/// transparent pixels blended over white
+fn luma(pixel: &[u8]) -> u8 {
+    let (value, alpha) = match *pixel {
+        [gray] => (gray as u32, 255),
+        [gray, alpha] => (gray as u32, alpha as u32),
+        [r, g, b] => (
+            (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000,
+            255,
+        ),
+        [r, g, b, alpha] => (
+            (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000,
+            alpha as u32,
+        ),
+        _ => (255, 255),
+    };
+    ((value * alpha + 255 * (255 - alpha)) / 255) as u8
+}
+
+/// Width, height and grayscale pixels of a PNG image
+fn read_png(image: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(image));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    let samples = info.color_type.samples();
+    let pixels = buffer
+        .chunks(info.line_size)
+        .take(info.height as usize)
+        .flat_map(|line| {
+            line.chunks(samples).take(info.width as usize).map(luma)
+        })
+        .collect();
+    Some((info.width as usize, info.height as usize, pixels))
+}
+
+/// Width, height and grayscale pixels of a JPEG image
+fn read_jpeg(image: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+    let mut decoder = jpeg_decoder::Decoder::new(image);
+    let buffer = decoder.decode().ok()?;
+    let info = decoder.info()?;
+    let pixels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => buffer,
+        // big endian samples
+        jpeg_decoder::PixelFormat::L16 => {
+            buffer.chunks(2).map(|sample| sample[0]).collect()
+        }
+        jpeg_decoder::PixelFormat::RGB24 => {
+            buffer.chunks(3).map(luma).collect()
+        }
+        jpeg_decoder::PixelFormat::CMYK32 => return None,
+    };
+    Some((info.width as usize, info.height as usize, pixels))
+}
+
+pub fn scan(image: &[u8]) -> Result<String, ScanQrError> {
+    //! Find and read the deck code of a QR code in a PNG or JPEG image
+    //!
+    //! Every QR code in the image is read and the first one starting with
+    //! PREFIX is returned. The deck code itself is not decoded.
+    let (width, height, pixels) = if image.starts_with(b"\x89PNG") {
+        read_png(image)
+    } else if image.starts_with(&[0xFF, 0xD8]) {
+        read_jpeg(image)
+    } else {
+        None
+    }
+    .filter(|(width, height, pixels)| pixels.len() == width * height)
+    .ok_or(ScanQrError::Image)?;
+    let mut prepared_image =
+        rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| {
+            pixels[y * width + x]
+        });
+    let payloads: Vec<String> = prepared_image
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok())
+        .map(|(_, payload)| payload)
+        .collect();
+    match payloads.iter().find(|payload| payload.starts_with(PREFIX)) {
+        Some(deck_code) => Ok(deck_code.clone()),
+        None => Err(match payloads.into_iter().next() {
+            Some(payload) => ScanQrError::NotDeckCode { payload },
+            None => ScanQrError::NotFound,
+        }),
+    }
+}
@@ -0,0 +1,143 @@
+//! Deck statistics
+
+use crate::carddb::{CardData, CardDb};
+use crate::codec::{Card, Deck, Language};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Card counts of a deck's digi-egg deck and main deck by card data
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeckStats {
+    /// count of digi-egg and main deck cards
+    pub cards: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// cards missing from the card database, left out of every other count
+    pub unknown_cards: Vec<Card>,
+    /// card count by category ("digimon", "digi-egg", "tamer" or "option")
+    pub categories: BTreeMap<String, u32>,
+    /// card count by color, counting multicolor cards once per color
+    pub colors: BTreeMap<String, u32>,
+    /// card count by level
+    pub levels: BTreeMap<u8, u32>,
+    /// card count by play cost, or use cost for options
+    pub play_costs: BTreeMap<u8, u32>,
+    /// digimon count by their cheapest digivolve cost
+    pub digivolve_costs: BTreeMap<u8, u32>,
+    /// card count by form, attribute and type
+    pub traits: BTreeMap<String, u32>,
+    /// count of cards with a security effect
+    pub security_effects: u32,
+}
+
+/// Form, attribute and types of a card, with types split on "/"
+fn traits(card_data: &CardData) -> Vec<&str> {
+    let mut traits: Vec<&str> = card_data
+        .form
+        .iter()
+        .chain(&card_data.attribute)
+        .map(String::as_str)
+        .collect();
+    if let Some(card_type) = &card_data.card_type {
+        traits.extend(card_type.split(['/', '／']).map(str::trim));
+    }
+    traits.retain(|t| !t.is_empty());
+    traits.sort_unstable();
+    traits.dedup();
+    traits
+}
+
+impl DeckStats {
+    /// Statistics of the digi-egg deck and main deck from `card_db`, in the
+    /// deck language or English for decks without one
+    ///
+    /// The sideboard is left out. Alternate arts missing from the card
+    /// database use the card data of the original art.
+    pub fn compute(deck: &Deck, card_db: &CardDb) -> DeckStats {
+        let language = deck.language.as_ref().unwrap_or(&Language::English);
+        let mut stats = DeckStats::default();
+        for card in deck.digi_eggs.iter().chain(&deck.deck) {
+            let count = u32::from(card.count);
+            stats.cards += count;
+            let card_data = match card_db.card(card, language) {
+                Some(card_data) => card_data,
+                None => {
+                    stats.unknown_cards.push(card.clone());
+                    continue;
+                }
+            };
+            *stats
+                .categories
+                .entry(card_data.category.clone())
+                .or_insert(0) += count;
+            for color in &card_data.colors {
+                *stats.colors.entry(color.clone()).or_insert(0) += count;
+            }
+            if let Some(level) = card_data.level {
+                *stats.levels.entry(level).or_insert(0) += count;
+            }
+            if let Some(cost) = card_data.play_cost.or(card_data.use_cost) {
+                *stats.play_costs.entry(cost).or_insert(0) += count;
+            }
+            if let Some(cost) = card_data
+                .digivolution_requirements
+                .iter()
+                .filter_map(|requirement| requirement.cost)
+                .min()
+            {
+                *stats.digivolve_costs.entry(cost).or_insert(0) += count;
+            }
+            for t in traits(card_data) {
+                *stats.traits.entry(t.to_string()).or_insert(0) += count;
+            }
+            if card_data
+                .security_effect
+                .as_ref()
+                .is_some_and(|effect| !effect.trim().is_empty())
+            {
+                stats.security_effects += count;
+            }
+        }
+        stats
+    }
+}
+
+fn fmt_counts<K: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    prefix: &str,
+    counts: &BTreeMap<K, u32>,
+) -> fmt::Result {
+    if counts.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{}", title)?;
+    for (key, count) in counts {
+        writeln!(f, "  {}{}: {}", prefix, key, count)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for DeckStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Cards: {}", self.cards)?;
+        if !self.unknown_cards.is_empty() {
+            writeln!(f, "Not in card database")?;
+            for card in &self.unknown_cards {
+                write!(f, "  {} {}", card.count, card.number)?;
+                if card.parallel_id > 0 {
+                    write!(f, " (P{})", card.parallel_id)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        fmt_counts(f, "Categories", "", &self.categories)?;
+        fmt_counts(f, "Colors", "", &self.colors)?;
+        fmt_counts(f, "Levels", "Lv.", &self.levels)?;
+        fmt_counts(f, "Play costs", "", &self.play_costs)?;
+        fmt_counts(f, "Digivolve costs", "", &self.digivolve_costs)?;
+        fmt_counts(f, "Traits", "", &self.traits)?;
+        writeln!(f, "Security effects: {}", self.security_effects)
+    }
+}
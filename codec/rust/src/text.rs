@@ -0,0 +1,183 @@
+//! Plain-text deck lists
+
+use crate::codec::{Card, Deck};
+use crate::normalize::PaddingRules;
+use std::fmt::{self, Write};
+
+/// Error returned when a line of a deck list can't be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDeckTextError {
+    /// line number starting at 1
+    pub line: usize,
+    /// text of the line
+    pub text: String,
+}
+
+impl fmt::Display for ParseDeckTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid deck list line {}: '{}'", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseDeckTextError {}
+
+/// Deck sections in the order they are written
+#[derive(Clone, Copy)]
+enum Section {
+    DigiEggs,
+    Deck,
+    Sideboard,
+}
+
+/// Read a section header such as "Digi-Egg", "Main Deck (50)" or
+/// "Sideboard:"
+fn parse_section(line: &str) -> Option<Section> {
+    let mut header = line.trim_end_matches(':').trim();
+    if let Some((title, _)) =
+        header.strip_suffix(')').and_then(|h| h.rsplit_once('('))
+    {
+        header = title.trim();
+    }
+    match header.to_lowercase().as_str() {
+        "digi-egg" | "digi-eggs" | "digi-egg deck" | "digi egg"
+        | "digi eggs" | "egg" | "eggs" => Some(Section::DigiEggs),
+        "main" | "main deck" | "deck" => Some(Section::Deck),
+        "sideboard" | "side" => Some(Section::Sideboard),
+        _ => None,
+    }
+}
+
+/// Read a card count such as "4", "4x" or "x4"
+fn parse_count(token: &str) -> Option<u8> {
+    let count = token
+        .strip_suffix(['x', 'X'])
+        .or_else(|| token.strip_prefix(['x', 'X']))
+        .unwrap_or(token);
+    count.parse().ok().filter(|&count| count > 0)
+}
+
+/// Read a card line such as "4 ST1-03 Agumon", "4x BT1-009" or
+/// "2 BT1-085 (P1) Agumon"
+fn parse_card(line: &str) -> Option<Card> {
+    let mut tokens = line.split_whitespace().peekable();
+    let count = parse_count(tokens.next()?)?;
+    if tokens.peek().is_some_and(|t| t.eq_ignore_ascii_case("x")) {
+        tokens.next();
+    }
+    // case and separators are fixed, zero padding is kept as typed
+    let number = PaddingRules::new().normalize(tokens.next()?).ok()?;
+    let parallel_id = tokens
+        .next()
+        .and_then(|t| t.strip_prefix("(P").or_else(|| t.strip_prefix("(p")))
+        .and_then(|t| t.strip_suffix(')'))
+        .and_then(|parallel_id| parallel_id.parse().ok())
+        .unwrap_or(0);
+    Some(Card {
+        number: number.to_string(),
+        parallel_id,
+        count,
+    })
+}
+
+impl Deck {
+    /// Read a plain-text deck list
+    ///
+    /// Each card is a line with a count and a card number, optionally
+    /// followed by a parallel id such as "(P1)" and the card name, which is
+    /// ignored. Counts may be written with or without "x" ("4", "4x" or
+    /// "x4"). Card numbers are upper cased and may use "_" instead of "-",
+    /// but keep their zero padding as typed. Cards go to the main deck until
+    /// a "Digi-Egg", "Main" or "Sideboard" header starts another section.
+    /// "Name:", "Icon:" and "Language:" lines set the deck metadata. Blank
+    /// lines and lines starting with "#" or "//" are skipped.
+    pub fn from_text(text: &str) -> Result<Deck, ParseDeckTextError> {
+        let mut deck = Deck {
+            digi_eggs: vec![],
+            deck: vec![],
+            sideboard: vec![],
+            icon: None,
+            language: None,
+            name: String::new(),
+        };
+        let mut section = Section::Deck;
+        for (i, line) in text.lines().enumerate() {
+            let error = || ParseDeckTextError {
+                line: i + 1,
+                text: line.to_string(),
+            };
+            let line = line.trim();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("//")
+            {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim().to_lowercase().as_str() {
+                    "name" => {
+                        deck.name = value.to_string();
+                        continue;
+                    }
+                    "icon" => {
+                        deck.icon = Some(value.to_string());
+                        continue;
+                    }
+                    "language" => {
+                        deck.language =
+                            Some(value.parse().map_err(|_| error())?);
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(header) = parse_section(line) {
+                section = header;
+                continue;
+            }
+            let card = parse_card(line).ok_or_else(error)?;
+            match section {
+                Section::DigiEggs => deck.digi_eggs.push(card),
+                Section::Deck => deck.deck.push(card),
+                Section::Sideboard => deck.sideboard.push(card),
+            }
+        }
+        Ok(deck)
+    }
+
+    /// Write the deck as a plain-text deck list that `from_text` reads back
+    /// to the same deck
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if !self.name.is_empty() {
+            writeln!(text, "Name: {}", self.name).unwrap();
+        }
+        if let Some(icon) = &self.icon {
+            writeln!(text, "Icon: {}", icon).unwrap();
+        }
+        if let Some(language) = &self.language {
+            writeln!(text, "Language: {}", language).unwrap();
+        }
+        for (title, cards) in [
+            ("Digi-Egg", &self.digi_eggs),
+            ("Main", &self.deck),
+            ("Sideboard", &self.sideboard),
+        ] {
+            if cards.is_empty() {
+                continue;
+            }
+            if !text.is_empty() {
+                writeln!(text).unwrap();
+            }
+            writeln!(text, "{}", title).unwrap();
+            for card in cards {
+                write!(text, "{} {}", card.count, card.number).unwrap();
+                if card.parallel_id > 0 {
+                    write!(text, " (P{})", card.parallel_id).unwrap();
+                }
+                writeln!(text).unwrap();
+            }
+        }
+        text
+    }
+}
@@ -0,0 +1,264 @@
+//! Tabletop Simulator saved objects
+
+use crate::codec::{Card, Deck, Language};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Card image URLs of a Tabletop Simulator export
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TtsOptions {
+    /// card face URL template
+    ///
+    /// `{language}`, `{number}` and `{parallel_id}` are replaced by the card
+    /// language tag, card number and parallel id, `{id}` by the db card id
+    /// (i.e. "en_BT5-012_P1") and `{image}` by the db image name, which only
+    /// has a parallel id suffix for alternate arts (i.e. "BT5-012_P1").
+    pub face_url_template: String,
+    /// card back URL
+    pub back_url: String,
+    /// card face language of decks without a language
+    pub language: Language,
+}
+
+impl TtsOptions {
+    /// Card face URL of a card
+    pub fn face_url(&self, language: &Language, card: &Card) -> String {
+        let image = if card.parallel_id > 0 {
+            format!("{}_P{}", card.number, card.parallel_id)
+        } else {
+            card.number.clone()
+        };
+        self.face_url_template
+            .replace(
+                "{id}",
+                &format!("{}_{}_P{}", language, card.number, card.parallel_id),
+            )
+            .replace("{image}", &image)
+            .replace("{language}", &language.to_string())
+            .replace("{number}", &card.number)
+            .replace("{parallel_id}", &card.parallel_id.to_string())
+    }
+}
+
+/// Position, rotation and scale of an object
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsTransform {
+    /// x position
+    pub pos_x: f32,
+    /// y position
+    pub pos_y: f32,
+    /// z position
+    pub pos_z: f32,
+    /// x rotation
+    pub rot_x: f32,
+    /// y rotation
+    pub rot_y: f32,
+    /// z rotation, 180 to lay cards face down
+    pub rot_z: f32,
+    /// x scale
+    pub scale_x: f32,
+    /// y scale
+    pub scale_y: f32,
+    /// z scale
+    pub scale_z: f32,
+}
+
+impl TtsTransform {
+    /// Face down at `pos_x` on the table
+    fn face_down(pos_x: f32) -> Self {
+        TtsTransform {
+            pos_x,
+            pos_y: 1.0,
+            pos_z: 0.0,
+            rot_x: 0.0,
+            rot_y: 180.0,
+            rot_z: 180.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            scale_z: 1.0,
+        }
+    }
+}
+
+/// Card sheet of one card image
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TtsCustomDeck {
+    #[serde(rename = "FaceURL")]
+    /// card face URL
+    pub face_url: String,
+    #[serde(rename = "BackURL")]
+    /// card back URL
+    pub back_url: String,
+    /// count of card images across the sheet
+    pub num_width: u8,
+    /// count of card images down the sheet
+    pub num_height: u8,
+    /// whether the back is shown for cards hidden in a hand
+    pub back_is_hidden: bool,
+    /// whether every card of the sheet has its own back
+    pub unique_back: bool,
+    #[serde(rename = "Type")]
+    /// card shape, 0 is a rectangle
+    pub card_type: u8,
+}
+
+/// A card or deck object
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TtsObject {
+    /// "Card" or "DeckCustom"
+    pub name: String,
+    /// position of the object
+    pub transform: TtsTransform,
+    /// name shown in Tabletop Simulator
+    pub nickname: String,
+    #[serde(rename = "CardID", skip_serializing_if = "Option::is_none")]
+    /// card sheet key times 100 of a card
+    pub card_id: Option<u32>,
+    #[serde(rename = "DeckIDs", skip_serializing_if = "Vec::is_empty")]
+    /// card id of every card in a deck from top to bottom
+    pub deck_ids: Vec<u32>,
+    /// card sheets by key
+    pub custom_deck: BTreeMap<u32, TtsCustomDeck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// cards in a deck
+    pub contained_objects: Vec<TtsObject>,
+}
+
+/// A saved object that can be put in Tabletop Simulator's Saved Objects
+/// folder
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TtsSave {
+    /// deck name
+    pub save_name: String,
+    /// always empty for saved objects
+    pub game_mode: String,
+    /// always 0.5 for saved objects
+    pub gravity: f32,
+    /// always 0.5 for saved objects
+    pub play_area: f32,
+    /// always empty for saved objects
+    pub date: String,
+    /// always empty for saved objects
+    pub table: String,
+    /// always empty for saved objects
+    pub sky: String,
+    /// always empty for saved objects
+    pub note: String,
+    /// always empty for saved objects
+    pub rules: String,
+    #[serde(rename = "XmlUI")]
+    /// always empty for saved objects
+    pub xml_ui: String,
+    /// always empty for saved objects
+    pub lua_script: String,
+    /// always empty for saved objects
+    pub lua_script_state: String,
+    /// digi-egg deck, main deck and sideboard deck objects
+    pub object_states: Vec<TtsObject>,
+    /// always empty for saved objects
+    pub tab_states: BTreeMap<String, String>,
+    /// always empty for saved objects
+    pub version_number: String,
+}
+
+/// Deck object of a deck section, or a card object if it only has one card
+fn section_object(
+    nickname: &str,
+    cards: &[Card],
+    pos_x: f32,
+    language: &Language,
+    options: &TtsOptions,
+    next_key: &mut u32,
+) -> Option<TtsObject> {
+    let mut deck_object = TtsObject {
+        name: "DeckCustom".to_string(),
+        transform: TtsTransform::face_down(pos_x),
+        nickname: nickname.to_string(),
+        card_id: None,
+        deck_ids: vec![],
+        custom_deck: BTreeMap::new(),
+        contained_objects: vec![],
+    };
+    for card in cards {
+        let key = *next_key;
+        *next_key += 1;
+        let custom_deck = TtsCustomDeck {
+            face_url: options.face_url(language, card),
+            back_url: options.back_url.clone(),
+            num_width: 1,
+            num_height: 1,
+            back_is_hidden: true,
+            unique_back: false,
+            card_type: 0,
+        };
+        let card_object = TtsObject {
+            name: "Card".to_string(),
+            transform: TtsTransform::face_down(pos_x),
+            nickname: card.number.clone(),
+            card_id: Some(key * 100),
+            deck_ids: vec![],
+            custom_deck: BTreeMap::from([(key, custom_deck.clone())]),
+            contained_objects: vec![],
+        };
+        deck_object.custom_deck.insert(key, custom_deck);
+        for _ in 0..card.count {
+            deck_object.deck_ids.push(key * 100);
+            deck_object.contained_objects.push(card_object.clone());
+        }
+    }
+    // Tabletop Simulator only loads decks of at least 2 cards
+    match deck_object.contained_objects.len() {
+        0 => None,
+        1 => deck_object.contained_objects.pop(),
+        _ => Some(deck_object),
+    }
+}
+
+impl Deck {
+    /// Tabletop Simulator saved object with a face down deck object for the
+    /// digi-egg deck, main deck and sideboard
+    ///
+    /// Card images are only referenced by URL, nothing is downloaded.
+    pub fn to_tts(&self, options: &TtsOptions) -> TtsSave {
+        let language = self.language.as_ref().unwrap_or(&options.language);
+        let mut next_key = 1;
+        let object_states = [
+            ("Digi-Egg", &self.digi_eggs, -3.0),
+            ("Main", &self.deck, 0.0),
+            ("Sideboard", &self.sideboard, 3.0),
+        ]
+        .iter()
+        .filter_map(|(nickname, cards, pos_x)| {
+            section_object(
+                nickname,
+                cards,
+                *pos_x,
+                language,
+                options,
+                &mut next_key,
+            )
+        })
+        .collect();
+        TtsSave {
+            save_name: self.name.clone(),
+            game_mode: String::new(),
+            gravity: 0.5,
+            play_area: 0.5,
+            date: String::new(),
+            table: String::new(),
+            sky: String::new(),
+            note: String::new(),
+            rules: String::new(),
+            xml_ui: String::new(),
+            lua_script: String::new(),
+            lua_script_state: String::new(),
+            object_states,
+            tab_states: BTreeMap::new(),
+            version_number: String::new(),
+        }
+    }
+}
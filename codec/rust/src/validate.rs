@@ -0,0 +1,221 @@
+//! Deck construction rules
+
+use crate::codec::{Card, Deck};
+use crate::normalize::copy_key;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Deck construction rules of a format
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    /// count of main deck cards
+    pub deck_size: u32,
+    /// least count of digi-egg cards
+    pub min_digi_eggs: u32,
+    /// most count of digi-egg cards
+    pub max_digi_eggs: u32,
+    /// most count of sideboard cards
+    pub max_sideboard: u32,
+    /// most copies of a card number across parallel ids and sections
+    pub max_copies: u32,
+    copy_limits: HashMap<String, u32>,
+}
+
+impl Default for RuleSet {
+    /// Official rules: 50 main deck cards, 0-5 digi-egg cards, a sideboard
+    /// of up to 10 cards and 4 copies of a card number, except BT6-085
+    /// which allows up to 50 copies
+    fn default() -> Self {
+        let mut rule_set = RuleSet {
+            deck_size: 50,
+            min_digi_eggs: 0,
+            max_digi_eggs: 5,
+            max_sideboard: 10,
+            max_copies: 4,
+            copy_limits: HashMap::new(),
+        };
+        rule_set.set_copy_limit("BT6-085", 50);
+        rule_set
+    }
+}
+
+impl RuleSet {
+    /// Allow `max_copies` copies of a card number instead of the rule set's
+    /// `max_copies`
+    pub fn set_copy_limit(&mut self, number: &str, max_copies: u32) {
+        self.copy_limits.insert(copy_key(number), max_copies);
+    }
+
+    /// Most copies allowed of a card number
+    pub fn copy_limit(&self, number: &str) -> u32 {
+        self.copy_limits
+            .get(&copy_key(number))
+            .copied()
+            .unwrap_or(self.max_copies)
+    }
+}
+
+/// A deck construction rule a deck breaks
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "violation", rename_all = "kebab-case")]
+pub enum Violation {
+    /// main deck does not have `expected` cards
+    DeckSize {
+        /// count of main deck cards
+        count: u32,
+        /// count of main deck cards required
+        expected: u32,
+    },
+    /// digi-egg deck has fewer than `min` or more than `max` cards
+    DigiEggCount {
+        /// count of digi-egg cards
+        count: u32,
+        /// least count of digi-egg cards allowed
+        min: u32,
+        /// most count of digi-egg cards allowed
+        max: u32,
+    },
+    /// sideboard has more than `max` cards
+    SideboardSize {
+        /// count of sideboard cards
+        count: u32,
+        /// most count of sideboard cards allowed
+        max: u32,
+    },
+    /// more than `max` copies of a card number
+    TooManyCopies {
+        /// card number
+        number: String,
+        /// copies across parallel ids and sections
+        count: u32,
+        /// most copies allowed
+        max: u32,
+    },
+    /// a banned card number is in the deck
+    Banned {
+        /// card number
+        number: String,
+        /// copies across parallel ids and sections
+        count: u32,
+    },
+    /// more than `max` copies of a restricted card number
+    Restricted {
+        /// card number
+        number: String,
+        /// copies across parallel ids and sections
+        count: u32,
+        /// most copies allowed while restricted
+        max: u32,
+    },
+    /// both card numbers of a banned pair are in the deck
+    BannedPair {
+        /// card number
+        number: String,
+        /// card number it can't be played with
+        paired_number: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DeckSize { count, expected } => write!(
+                f,
+                "Main deck has {} cards instead of {}",
+                count, expected
+            ),
+            Violation::DigiEggCount { count, min, max } => write!(
+                f,
+                "Digi-egg deck has {} cards instead of {}-{}",
+                count, min, max
+            ),
+            Violation::SideboardSize { count, max } => {
+                write!(f, "Sideboard has {} cards, more than {}", count, max)
+            }
+            Violation::TooManyCopies { number, count, max } => {
+                write!(f, "{} has {} copies, more than {}", number, count, max)
+            }
+            Violation::Banned { number, count } => {
+                write!(f, "{} is banned ({} copies)", number, count)
+            }
+            Violation::Restricted { number, count, max } => write!(
+                f,
+                "{} is restricted to {} but has {} copies",
+                number, max, count
+            ),
+            Violation::BannedPair {
+                number,
+                paired_number,
+            } => {
+                write!(f, "{} and {} are a banned pair", number, paired_number)
+            }
+        }
+    }
+}
+
+/// Copies of every card number of a deck across parallel ids and sections
+pub(crate) fn copies(deck: &Deck) -> BTreeMap<String, u32> {
+    let mut copies: BTreeMap<String, u32> = BTreeMap::new();
+    for card in deck
+        .digi_eggs
+        .iter()
+        .chain(&deck.deck)
+        .chain(&deck.sideboard)
+    {
+        *copies.entry(copy_key(&card.number)).or_insert(0) +=
+            u32::from(card.count);
+    }
+    copies
+}
+
+/// Count of cards of a deck section
+fn card_count(cards: &[Card]) -> u32 {
+    cards.iter().map(|card| u32::from(card.count)).sum()
+}
+
+impl Deck {
+    /// Deck construction rules of `rule_set` the deck breaks
+    ///
+    /// Copies of a card number are counted across parallel ids and every
+    /// section including the sideboard, with card numbers compared
+    /// regardless of case and zero padding.
+    pub fn validate(&self, rule_set: &RuleSet) -> Vec<Violation> {
+        let mut violations = vec![];
+        let deck_count = card_count(&self.deck);
+        if deck_count != rule_set.deck_size {
+            violations.push(Violation::DeckSize {
+                count: deck_count,
+                expected: rule_set.deck_size,
+            });
+        }
+        let digi_egg_count = card_count(&self.digi_eggs);
+        if digi_egg_count < rule_set.min_digi_eggs
+            || digi_egg_count > rule_set.max_digi_eggs
+        {
+            violations.push(Violation::DigiEggCount {
+                count: digi_egg_count,
+                min: rule_set.min_digi_eggs,
+                max: rule_set.max_digi_eggs,
+            });
+        }
+        let sideboard_count = card_count(&self.sideboard);
+        if sideboard_count > rule_set.max_sideboard {
+            violations.push(Violation::SideboardSize {
+                count: sideboard_count,
+                max: rule_set.max_sideboard,
+            });
+        }
+        for (number, count) in copies(self) {
+            let max = rule_set.copy_limit(&number);
+            if count > max {
+                violations.push(Violation::TooManyCopies {
+                    number,
+                    count,
+                    max,
+                });
+            }
+        }
+        violations
+    }
+}
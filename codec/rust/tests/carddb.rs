@@ -0,0 +1,109 @@
+use dcg_codec::carddb::{CardData, CardDb, EnrichedCard, LoadCardDbError};
+use dcg_codec::codec::{decode, Card, Language};
+
+mod common;
+use common::ST1_DECK_ENCODED;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BULK_DATA_JSON: &str = r#"[
+{"data":{"type":"card","id":"/cards/en/ST1-01","attributes":{"name":"Koromon","number":"ST1-01","category":"digi-egg","parallel-id":0,"level":2,"dp":null,"play-cost":null,"language":"en","form":"In-Training","type":"Lesser","rarity":"U","color":["red"],"image":"/images/cards/en/ST1-01.png"},"links":{"self":"/cards/en/ST1-01"}}},
+{"data":{"type":"card","id":"/cards/ja/ST1-01","attributes":{"name":"コロモン","number":"ST1-01","category":"digi-egg","parallel-id":0,"level":2,"language":"ja","type":"レッサー型","rarity":"U","color":["red"]}}},
+{"data":{"type":"card","id":"/cards/en/ST1-03_P1","attributes":{"name":"Agumon","number":"ST1-03","category":"digimon","parallel-id":1,"level":3,"dp":2000,"play-cost":3,"language":"en","type":"Reptile","rarity":"C","color":["red"]}}},
+{"data":{"type":"card","id":"/cards/en/ST1-16","attributes":{"name":"Gaia Force","number":"ST1-16","category":"option","parallel-id":0,"use-cost":6,"language":"en","rarity":"C","color":["red"]}}}
+]"#;
+
+    #[test]
+    fn card_db() {
+        let card_db = CardDb::from_json(BULK_DATA_JSON).unwrap();
+        assert_eq!(card_db.len(), 4);
+        assert_eq!(
+            card_db.get("st1-1", 0, &Language::English),
+            Some(&CardData {
+                name: "Koromon".to_string(),
+                category: "digi-egg".to_string(),
+                colors: vec!["red".to_string()],
+                level: Some(2),
+                card_type: Some("Lesser".to_string()),
+                play_cost: None,
+                use_cost: None,
+                rarity: Some("U".to_string()),
+                form: Some("In-Training".to_string()),
+                attribute: None,
+                digivolution_requirements: vec![],
+                security_effect: None,
+            })
+        );
+        assert_eq!(
+            card_db
+                .get("ST1-01", 0, &Language::Japanese)
+                .map(|card_data| card_data.name.as_str()),
+            Some("コロモン")
+        );
+        assert_eq!(card_db.get("ST1-01", 0, &Language::Korean), None);
+        assert_eq!(card_db.get("ST1-03", 0, &Language::English), None);
+        // alternate arts fall back to the original art
+        let card = Card {
+            number: "ST1-16".to_string(),
+            parallel_id: 2,
+            count: 2,
+        };
+        assert_eq!(
+            card_db
+                .card(&card, &Language::English)
+                .and_then(|card_data| card_data.use_cost),
+            Some(6)
+        );
+
+        assert!(matches!(
+            CardDb::from_json("{}"),
+            Err(LoadCardDbError::Parse(_))
+        ));
+        assert!(matches!(
+            CardDb::load("cards-that-do-not-exist.json"),
+            Err(LoadCardDbError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn enrich_deck() {
+        let card_db = CardDb::from_json(BULK_DATA_JSON).unwrap();
+        let mut deck = decode(ST1_DECK_ENCODED);
+        let enriched_deck = deck.enrich(&card_db, &Language::English);
+        assert_eq!(enriched_deck.name, deck.name);
+        assert_eq!(
+            enriched_deck.digi_eggs[0]
+                .data
+                .as_ref()
+                .map(|card_data| card_data.name.as_str()),
+            Some("Koromon")
+        );
+        assert_eq!(enriched_deck.deck[0].data, None);
+        let json = serde_json::to_string(&enriched_deck.digi_eggs[0]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"number":"ST1-01","count":4,"name":"Koromon","category":"digi-egg","color":["red"],"level":2,"type":"Lesser","rarity":"U","form":"In-Training"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&EnrichedCard {
+                card: deck.deck[0].clone(),
+                data: None,
+            })
+            .unwrap(),
+            r#"{"number":"ST1-02","count":4}"#
+        );
+
+        // the deck language is used over the fallback language
+        deck.language = Some(Language::Japanese);
+        let enriched_deck = deck.enrich(&card_db, &Language::English);
+        assert_eq!(
+            enriched_deck.digi_eggs[0]
+                .data
+                .as_ref()
+                .map(|card_data| card_data.name.as_str()),
+            Some("コロモン")
+        );
+    }
+}
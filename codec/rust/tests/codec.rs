@@ -1,4 +1,14 @@
-use dcg_codec::codec::{decode, encode};
+use dcg_codec::codec::{
+    compute_crc16, decode, encode, encode_bytes_into, encode_canonical,
+    encode_into, encode_minimal, inspect, minimal_version, transcode,
+    try_decode, try_decode_verified, try_encode, Card, CardNumber,
+    ChecksumAlgorithm, Deck, DeckHeader, DecodeError, EncodeError, Encoder,
+    Language, LossWarning, ParseCardNumberError, ParseLanguageError,
+    MAX_VERSION, VERSION,
+};
+
+mod common;
+use common::{card, ST1_DECK_ENCODED};
 
 #[cfg(test)]
 mod tests {
@@ -14,8 +24,6 @@ mod tests {
     fn codec_roundtrip() {
         // v0
         let digi_bros_deck_encoded = "DCGApQzQlQyIIHBU1QxIEEBQlQxIIQFAsYCQU0QQlQyIIHEBEJUMyCGxALFAYNCwYUNU1QxIEbCwYMBiEUCRGlnaSBCcm9zOiBSYWduYWxvYXJkbW9uIFJlZCAoeW91dHUuYmUvbzBLb1cyd3doUjQp";
-        // v1
-        let st1_deck_encoded = "DCGETsdnJ0BQQMBnJ0BTwMCAwEDAQMBAwEBAQMBAwEBAQEBAwEDAQMBAQEBAVN0YXJ0ZXIgRGVjaywgR2FpYSBSZWQgW1NULTFd";
         // v2
         let deck_with_sideboard_encoded = "DCGIkA_B4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCV9fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
         // v3
@@ -28,7 +36,7 @@ mod tests {
         let deck_with_sideboard_and_language_ko_and_icon_encoded = "DCGUsD_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
 
         roundtrip_with_version(digi_bros_deck_encoded, 0);
-        roundtrip_with_version(st1_deck_encoded, 1);
+        roundtrip_with_version(ST1_DECK_ENCODED, 1);
         roundtrip_with_version(deck_with_sideboard_encoded, 2);
         roundtrip_with_version(deck_with_sideboard_and_language_encoded, 3);
         roundtrip_with_version(
@@ -43,5 +51,744 @@ mod tests {
             deck_with_sideboard_and_language_ko_and_icon_encoded,
             5,
         );
+        // v6
+        let digi_bros_deck_v6_encoded = "DCGYiZzAIudAoFhnJ0BQQGLnQGECWohsUAEi50CgXABi50DhmhlQyJhVQOcnQFGYmFDAcBAKURpZ2kgQnJvczogUmFnbmFsb2FyZG1vbiBSZWQgKHlvdXR1LmJlL28wS29XMnd3aFI0KQ";
+        // v6
+        let st1_deck_v6_encoded = "DCGYdVdAJydAUFhnJ0BT2JhYWFhIWFhISFhYWEhIVN0YXJ0ZXIgRGVjaywgR2FpYSBSZWQgW1NULTFd";
+        // v6
+        let deck_with_sideboard_and_language_zh_and_icon_v6_encoded = "DCGYly_h4udAoFhnJ0BQQGLnQGECWohsUAEi50CgXABi50DhWhlQyJhi50DgVgEnJ0BRmJhQwHAQClCQUNLLTAwMV9fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX18";
+
+        roundtrip_with_version(digi_bros_deck_v6_encoded, 6);
+        roundtrip_with_version(st1_deck_v6_encoded, 6);
+        roundtrip_with_version(
+            deck_with_sideboard_and_language_zh_and_icon_v6_encoded,
+            6,
+        );
+        // v6, a card group of 40 cards
+        let bt1_singles_v6_encoded = "DCGYPpLAIudAagBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAUJUMSBzaW5nbGVz";
+        roundtrip_with_version(bt1_singles_v6_encoded, 6);
+        let bt1_singles_deck = decode(bt1_singles_v6_encoded);
+        assert_eq!(bt1_singles_deck.deck.len(), 40);
+        assert_eq!(bt1_singles_deck.deck[39], card("BT1-040", 0, 1));
+        // v7
+        let deck_with_sideboard_and_language_zh_and_icon_v7_encoded = "DCGcvgxv4eLnQKBYZydAUEBi50BhAlqIbFABIudAoFwAYudA4VoZUMiYYudA4FYBJydAUZiYUMBwEApQkFDSy0wMDFfX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19f";
+        roundtrip_with_version(
+            deck_with_sideboard_and_language_zh_and_icon_v7_encoded,
+            7,
+        );
+        // v8
+        let deck_with_sideboard_and_language_zh_and_icon_v8_encoded = "DCGgiWIP4cDi50CgWGcnQFBAYudAYQJaiGxQASLnQKBcAGLnQOFaGVDImGLnQOBWAScnQFGYmFDAcBAKUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
+        roundtrip_with_version(
+            deck_with_sideboard_and_language_zh_and_icon_v8_encoded,
+            8,
+        );
+
+        // v6 stores the same decks in fewer bytes
+        for (deck_code, deck_v6_code) in [
+            (digi_bros_deck_encoded, digi_bros_deck_v6_encoded),
+            (ST1_DECK_ENCODED, st1_deck_v6_encoded),
+            (
+                deck_with_sideboard_and_language_zh_and_icon_encoded,
+                deck_with_sideboard_and_language_zh_and_icon_v6_encoded,
+            ),
+        ] {
+            let deck = decode(deck_code);
+            let deck_v6 = decode(deck_v6_code);
+            assert_eq!(deck.digi_eggs, deck_v6.digi_eggs);
+            assert_eq!(deck.deck, deck_v6.deck);
+            assert_eq!(deck.sideboard, deck_v6.sideboard);
+            assert_eq!(deck.name, deck_v6.name);
+            assert!(deck_v6_code.len() < deck_code.len());
+        }
+    }
+
+    #[test]
+    fn codec_v6_counts_and_parallel_ids() {
+        let mut deck = deck_with_card("BT6-085", 50, "");
+        deck.language = Some(Language::Japanese);
+        deck.deck.push(Card {
+            number: "BT1-085".to_string(),
+            parallel_id: 3,
+            count: 4,
+        });
+        deck.deck.push(Card {
+            number: "BT1-084".to_string(),
+            parallel_id: 0,
+            count: 1,
+        });
+        deck.canonicalize();
+
+        assert_eq!(decode(&encode(deck.clone(), 6)), deck);
+    }
+
+    #[test]
+    fn codec_large_card_group() {
+        // group counts past 5 bits carry into the next byte, next to the
+        // zero padding bits
+        let mut deck = deck_with_card("BT1-001", 1, "");
+        deck.deck = (1..=40)
+            .map(|number| card(&format!("BT1-{:03}", number), 0, 1))
+            .collect();
+        for version in 2..=MAX_VERSION {
+            let mut deck = deck.clone();
+            if version < 3 {
+                deck.language = None;
+            }
+            assert_eq!(decode(&encode(deck.clone(), version)), deck);
+        }
+    }
+
+    #[test]
+    fn codec_parallel_id_range() {
+        for parallel_id in [7, 8, 31, 32, 255] {
+            let mut deck = deck_with_card("BT16-083", 4, "");
+            deck.language = Some(Language::English);
+            deck.deck[0].parallel_id = parallel_id;
+            for version in 6..=MAX_VERSION {
+                assert_eq!(decode(&encode(deck.clone(), version)), deck);
+            }
+        }
+
+        let mut deck = deck_with_card("BT16-083", 4, "");
+        deck.deck[0].parallel_id = 8;
+        for version in 0..6 {
+            let mut deck = deck.clone();
+            if version < 3 {
+                deck.language = None;
+            }
+            assert_eq!(
+                try_encode(deck, version),
+                Err(EncodeError::InvalidParallelId {
+                    number: "BT16-083".to_string(),
+                    parallel_id: 8
+                })
+            );
+        }
+        assert_eq!(minimal_version(&deck), Ok(6));
+    }
+
+    #[test]
+    fn languages() {
+        for (language, tag) in [
+            (Language::Japanese, "ja"),
+            (Language::English, "en"),
+            (Language::Chinese, "zh-Hans"),
+            (Language::Korean, "ko"),
+            (Language::Unknown(9), "x-9"),
+        ] {
+            assert_eq!(language.to_string(), tag);
+            assert_eq!(tag.parse(), Ok(language.clone()));
+            assert_eq!(Language::from_number(language.number()), language);
+            assert_eq!(
+                serde_json::to_string(&language).unwrap(),
+                format!("{:?}", tag)
+            );
+        }
+        assert_eq!("ZH-hans".parse(), Ok(Language::Chinese));
+        assert_eq!("x-2".parse(), Ok(Language::Chinese));
+        assert_eq!(
+            "fr".parse::<Language>(),
+            Err(ParseLanguageError {
+                tag: "fr".to_string()
+            })
+        );
+        assert_eq!(
+            serde_json::from_str::<Language>(r#""Korean""#).unwrap(),
+            Language::Korean
+        );
+
+        // every language number a version can store is decoded and
+        // re-encoded as it was
+        let mut deck = deck_with_card("ST1-01", 4, "");
+        for version in 3..=MAX_VERSION {
+            let language_numbers = match version {
+                3..=4 => 0..=1,
+                5..=7 => 0..=3,
+                _ => 0..=254,
+            };
+            for language_number in language_numbers {
+                deck.language = Some(Language::from_number(language_number));
+                let deck_code = encode(deck.clone(), version);
+                assert_eq!(decode(&deck_code), deck);
+                assert_eq!(encode(decode(&deck_code), version), deck_code);
+            }
+        }
+
+        // v3 to v7 refuse to store an unspecified language as English
+        deck.language = None;
+        assert_eq!(decode(&encode(deck.clone(), 8)), deck);
+        assert_eq!(decode(&encode(deck.clone(), 2)), deck);
+        for version in 3..=7 {
+            assert_eq!(
+                try_encode(deck.clone(), version),
+                Err(EncodeError::Lossy {
+                    version,
+                    warnings: vec![LossWarning::UnspecifiedLanguage]
+                })
+            );
+        }
+        // v8 is opt-in
+        deck.icon = Some("ST1-01".to_string());
+        assert_eq!(
+            minimal_version(&deck),
+            Err(EncodeError::Lossy {
+                version: VERSION,
+                warnings: vec![LossWarning::UnspecifiedLanguage]
+            })
+        );
+        assert_eq!(
+            encode_minimal(&deck),
+            Err(EncodeError::Lossy {
+                version: VERSION,
+                warnings: vec![LossWarning::UnspecifiedLanguage]
+            })
+        );
+        deck.icon = None;
+
+        deck.language = Some(Language::Unknown(9));
+        assert_eq!(
+            try_encode(deck.clone(), 7),
+            Err(EncodeError::Lossy {
+                version: 7,
+                warnings: vec![LossWarning::LanguageNarrowed {
+                    from: Language::Unknown(9),
+                    to: Language::English
+                }]
+            })
+        );
+        assert_eq!(
+            try_encode(deck.clone(), 7).unwrap_err().to_string(),
+            "Deck version 7 can't store the deck: Language x-9 stored as en;"
+        );
+        assert!(matches!(
+            minimal_version(&deck),
+            Err(EncodeError::Lossy { version: 7, .. })
+        ));
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert!(try_decode(ST1_DECK_ENCODED).is_ok());
+
+        assert_eq!(
+            try_decode("XYZETsdnJ0BQQMB"),
+            Err(DecodeError::BadPrefix { offset: 0 })
+        );
+        assert_eq!(
+            try_decode("DCGETs*dnJ0"),
+            Err(DecodeError::BadBase64 { offset: 6 })
+        );
+        assert_eq!(
+            try_decode("DCG"),
+            Err(DecodeError::TruncatedHeader { offset: 0 })
+        );
+        // version 15
+        assert_eq!(
+            try_decode("DCG8AAA"),
+            Err(DecodeError::UnsupportedVersion {
+                offset: 0,
+                version: 15
+            })
+        );
+        // checksum byte altered
+        assert_eq!(
+            try_decode(&ST1_DECK_ENCODED.replacen("ETs", "ETo", 1)),
+            Err(DecodeError::ChecksumMismatch {
+                offset: 1,
+                expected: 0x3A,
+                computed: 0x3B,
+            })
+        );
+        // card section cut short (deck name removed)
+        let truncated = &ST1_DECK_ENCODED[..ST1_DECK_ENCODED.len() - 40];
+        assert!(matches!(
+            try_decode(truncated),
+            Err(DecodeError::ChecksumMismatch { .. })
+                | Err(DecodeError::TruncatedCards { .. })
+        ));
+        // v1, 0 digi-eggs, name length 1, one card set "A" with 2 cards
+        // but no card bytes
+        assert_eq!(
+            try_decode("DCGEAwBCgJA"),
+            Err(DecodeError::TruncatedCards { offset: 6 })
+        );
+        // v2, card set "A" whose card count carries past 32 bits
+        let card_bytes = [&[0, 0x0A, 0x20][..], &[0xFF; 8], &[0x00]].concat();
+        let checksum = card_bytes.iter().map(|&b| u32::from(b)).sum::<u32>();
+        let deck_bytes = [&[0x20, checksum as u8, 0][..], &card_bytes].concat();
+        assert_eq!(
+            try_decode(&format!(
+                "DCG{}",
+                base64::Engine::encode(
+                    &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                    deck_bytes
+                )
+            )),
+            Err(DecodeError::ValueOverflow { offset: 9 })
+        );
+        // v1, name length 1, no cards, name byte 0xFF
+        assert_eq!(
+            try_decode("DCGEAAB_w"),
+            Err(DecodeError::InvalidName { offset: 3 })
+        );
+        // v4, icon flag set with a 2 byte name
+        assert_eq!(
+            try_decode("DCGQIACgEFC"),
+            Err(DecodeError::IconOverflow { offset: 4 })
+        );
+        // v4, icon flag set with a 3 byte character across the icon's 8th
+        // byte
+        let deck_bytes = [&[0x40, 0x80, 12, 0x80][..], "アグモン".as_bytes()];
+        assert_eq!(
+            try_decode(&format!(
+                "DCG{}",
+                base64::Engine::encode(
+                    &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                    deck_bytes.concat()
+                )
+            )),
+            Err(DecodeError::IconOverflow { offset: 4 })
+        );
+    }
+
+    /// Deck of one card in English, which v3 to v7 need to encode it
+    /// losslessly
+    fn deck_with_card(number: &str, count: u8, name: &str) -> Deck {
+        Deck {
+            digi_eggs: vec![],
+            deck: vec![Card {
+                number: number.to_string(),
+                parallel_id: 0,
+                count,
+            }],
+            sideboard: vec![],
+            icon: None,
+            language: Some(Language::English),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn encode_errors() {
+        assert!(try_encode(deck_with_card("ST1-01", 4, ""), 5).is_ok());
+
+        for number in [
+            "ST101",
+            "ST1-",
+            "-01",
+            "ST1-01a",
+            "ST1-00001",
+            "st1-01",
+            "EX-1-001",
+            "ST_1-01",
+        ] {
+            assert_eq!(
+                try_encode(deck_with_card(number, 1, ""), 5),
+                Err(EncodeError::InvalidCardNumber {
+                    number: number.to_string()
+                })
+            );
+        }
+        // v0 card sets are limited to 4 bytes
+        let mut deck = deck_with_card("RB01-001", 1, "");
+        deck.language = None;
+        assert!(try_encode(deck.clone(), 1).is_ok());
+        deck.deck[0].number = "RB011-001".to_string();
+        assert!(try_encode(deck, 0).is_err());
+        assert_eq!(
+            try_encode(deck_with_card("ST1-01", 0, ""), 5),
+            Err(EncodeError::InvalidCardCount {
+                number: "ST1-01".to_string(),
+                count: 0
+            })
+        );
+    }
+
+    #[test]
+    fn card_numbers() {
+        for number in ["BT1-001", "P-001", "EX-1-001", "st1-01a", "BT1-0"] {
+            assert_eq!(
+                number.parse::<CardNumber>().unwrap().to_string(),
+                number
+            );
+        }
+        assert_eq!(
+            "EX-1-001a".parse(),
+            Ok(CardNumber {
+                card_set: "EX-1".to_string(),
+                number: 1,
+                padding: 3,
+                suffix: Some("a".to_string()),
+            })
+        );
+        for number in ["ST101", "ST1-", "-01", "ST1-a01", "ST1-99999999999"] {
+            assert_eq!(
+                number.parse::<CardNumber>(),
+                Err(ParseCardNumberError {
+                    number: number.to_string()
+                })
+            );
+        }
+        let card_number: CardNumber = "RB011-001".parse().unwrap();
+        assert!(card_number.is_encodable(1));
+        assert!(!card_number.is_encodable(0));
+        for number in ["bt1-001", "BT1-001a", "BT1-00001", "EX-1-001"] {
+            assert!(!number
+                .parse::<CardNumber>()
+                .unwrap()
+                .is_encodable(VERSION));
+        }
+
+        // card set characters outside of base36 and card numbers wider than
+        // their zero padding are rejected instead of decoding another card
+        let deck = deck_with_card("ST1-9", 4, "");
+        let corrupt_deck_code = |index: usize, byte: u8| {
+            let mut deck_bytes = Vec::new();
+            encode_bytes_into(&deck, 7, &mut deck_bytes).unwrap();
+            deck_bytes[index] = byte;
+            let checksum = compute_crc16(&deck_bytes);
+            deck_bytes[1..3].copy_from_slice(&checksum.to_be_bytes());
+            format!(
+                "DCG{}",
+                base64::Engine::encode(
+                    &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                    deck_bytes
+                )
+            )
+        };
+        assert_eq!(
+            try_decode(&corrupt_deck_code(5, 0x80 | 40)),
+            Err(DecodeError::InvalidCardSet { offset: 5 })
+        );
+        // card number offset 10 with a zero padding of 1
+        assert_eq!(
+            try_decode(&corrupt_deck_code(9, 0x60 | 10)),
+            Err(DecodeError::InvalidCardNumber { offset: 9 })
+        );
+    }
+
+    #[test]
+    fn encode_truncates_name_on_char_boundary() {
+        // 22 three byte characters is 66 bytes
+        let name = "デジモンカードゲームデジモンカードゲームデジ";
+        let deck_code =
+            try_encode(deck_with_card("ST1-01", 4, name), 5).unwrap();
+        let decoded_deck = try_decode(&deck_code).unwrap();
+        assert_eq!(
+            decoded_deck.name,
+            name.chars().take(21).collect::<String>()
+        );
+    }
+
+    #[test]
+    fn encode_multibyte_names_and_icons() {
+        // 21 three byte characters is exactly 63 bytes
+        let name = "デジモンカードゲームデジモンカードゲームデ";
+        for version in [1, 5, VERSION] {
+            let mut deck = deck_with_card("ST1-01", 4, name);
+            if version < 3 {
+                deck.language = None;
+            }
+            let deck_code = try_encode(deck, version).unwrap();
+            assert_eq!(try_decode(&deck_code).unwrap().name, name);
+        }
+
+        // icons take 8 of the 63 bytes, leaving 18 three byte characters
+        for icon in ["BT1-001", "デジ", "アグ!", "Agu mon", ""] {
+            let mut deck = deck_with_card("ST1-01", 4, name);
+            deck.icon = Some(icon.to_string());
+            let decoded_deck =
+                try_decode(&try_encode(deck, VERSION).unwrap()).unwrap();
+            assert_eq!(decoded_deck.icon.as_deref(), Some(icon));
+            assert_eq!(
+                decoded_deck.name,
+                name.chars().take(18).collect::<String>()
+            );
+        }
+
+        for icon in ["BT10-0001", "アグモンX", "デジモン"] {
+            let mut deck = deck_with_card("ST1-01", 4, "");
+            deck.icon = Some(icon.to_string());
+            assert_eq!(
+                try_encode(deck, VERSION),
+                Err(EncodeError::InvalidIcon {
+                    icon: icon.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn encode_into_borrowed_deck() {
+        let deck = decode(ST1_DECK_ENCODED);
+
+        let mut deck_code = String::new();
+        encode_into(&deck, 1, &mut deck_code).unwrap();
+        assert_eq!(ST1_DECK_ENCODED, deck_code);
+
+        // appends to the buffer without touching existing bytes
+        let mut deck_bytes = vec![0xFF];
+        encode_bytes_into(&deck, 1, &mut deck_bytes).unwrap();
+        encode_bytes_into(&deck, 1, &mut deck_bytes).unwrap();
+        let half = (deck_bytes.len() - 1) / 2;
+        assert_eq!(deck_bytes[0], 0xFF);
+        assert_eq!(deck_bytes[1..=half], deck_bytes[half + 1..]);
+
+        // an encoder reuses its buffers across decks
+        let mut encoder = Encoder::new();
+        let mut sideboard_deck = deck.clone();
+        sideboard_deck.sideboard = deck.deck[..2].to_vec();
+        sideboard_deck.language = Some(Language::Korean);
+        let sideboard_deck = decode(&encode(sideboard_deck, 5));
+        for (deck, version) in [(&deck, 1), (&sideboard_deck, 5), (&deck, 1)] {
+            let mut deck_code = String::new();
+            encoder.encode_into(deck, version, &mut deck_code).unwrap();
+            assert_eq!(deck_code, encode(deck.clone(), version));
+        }
+    }
+
+    #[test]
+    fn inspect_header() {
+        // v5
+        let deck_with_sideboard_and_language_ko_and_icon_encoded = "DCGUsD_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
+        let deck = decode(deck_with_sideboard_and_language_ko_and_icon_encoded);
+        let header =
+            inspect(deck_with_sideboard_and_language_ko_and_icon_encoded)
+                .unwrap();
+        assert_eq!(
+            header,
+            DeckHeader {
+                version: 5,
+                checksum: 0xC0,
+                checksum_algorithm: ChecksumAlgorithm::Additive,
+                language: Some(Language::Korean),
+                digi_egg_count: deck.digi_eggs.len(),
+                sideboard_count: deck.sideboard.len(),
+                has_icon: true,
+                icon: deck.icon,
+                name: deck.name,
+            }
+        );
+
+        // v1 has no language, sideboard or icon
+        let header = inspect(ST1_DECK_ENCODED).unwrap();
+        assert_eq!(header.version, 1);
+        assert_eq!(header.language, None);
+        assert_eq!(header.sideboard_count, 0);
+        assert!(!header.has_icon);
+        assert_eq!(header.name, "Starter Deck, Gaia Red [ST-1]");
+    }
+
+    #[test]
+    fn transcode_with_loss_warnings() {
+        // v5
+        let deck_with_sideboard_and_language_zh_and_icon_encoded = "DCGUsC_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
+        let deck = decode(deck_with_sideboard_and_language_zh_and_icon_encoded);
+
+        let (deck_code, warnings) =
+            transcode(deck_with_sideboard_and_language_zh_and_icon_encoded, 5)
+                .unwrap();
+        assert_eq!(
+            deck_code,
+            deck_with_sideboard_and_language_zh_and_icon_encoded
+        );
+        assert!(warnings.is_empty());
+
+        let (deck_code, warnings) =
+            transcode(deck_with_sideboard_and_language_zh_and_icon_encoded, 4)
+                .unwrap();
+        assert_eq!(
+            warnings,
+            vec![LossWarning::LanguageNarrowed {
+                from: Language::Chinese,
+                to: Language::English
+            }]
+        );
+        let transcoded_deck = decode(&deck_code);
+        assert_eq!(transcoded_deck.language, Some(Language::English));
+        assert_eq!(transcoded_deck.icon, deck.icon);
+
+        let (deck_code, warnings) =
+            transcode(deck_with_sideboard_and_language_zh_and_icon_encoded, 1)
+                .unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                LossWarning::Icon("BACK-001".to_string()),
+                LossWarning::Language(Language::Chinese),
+                LossWarning::Sideboard(deck.sideboard.clone()),
+            ]
+        );
+        let transcoded_deck = decode(&deck_code);
+        assert_eq!(transcoded_deck.digi_eggs, deck.digi_eggs);
+        assert_eq!(transcoded_deck.deck, deck.deck);
+        assert!(transcoded_deck.sideboard.is_empty());
+        assert_eq!(transcoded_deck.icon, None);
+        assert_eq!(transcoded_deck.language, None);
+
+        assert!(transcode(
+            deck_with_sideboard_and_language_zh_and_icon_encoded,
+            15
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn encode_minimal_version() {
+        let mut deck = deck_with_card("ST1-01", 4, "");
+        deck.language = None;
+        assert_eq!(minimal_version(&deck), Ok(0));
+        assert_eq!(
+            decode(&encode_minimal(&deck).unwrap()),
+            decode(&encode(deck.clone(), 0))
+        );
+
+        deck.deck[0].count = 5;
+        assert_eq!(minimal_version(&deck), Ok(1));
+        assert_eq!(
+            try_encode(deck.clone(), 0),
+            Err(EncodeError::InvalidCardCount {
+                number: "ST1-01".to_string(),
+                count: 5
+            })
+        );
+
+        deck.sideboard = deck.deck.clone();
+        assert_eq!(minimal_version(&deck), Ok(2));
+        assert_eq!(
+            try_encode(deck.clone(), 1),
+            Err(EncodeError::Lossy {
+                version: 1,
+                warnings: vec![LossWarning::Sideboard(deck.sideboard.clone())]
+            })
+        );
+
+        deck.language = Some(Language::Japanese);
+        assert_eq!(minimal_version(&deck), Ok(3));
+
+        deck.icon = Some("ST1-01".to_string());
+        assert_eq!(minimal_version(&deck), Ok(4));
+
+        deck.language = Some(Language::Korean);
+        assert_eq!(minimal_version(&deck), Ok(5));
+        assert!(matches!(
+            try_encode(deck.clone(), 4),
+            Err(EncodeError::Lossy { version: 4, .. })
+        ));
+
+        let deck_code = encode_minimal(&deck).unwrap();
+        assert_eq!(inspect(&deck_code).unwrap().version, 5);
+        assert_eq!(decode(&deck_code), deck);
+    }
+
+    #[test]
+    fn canonicalize_deck() {
+        let mut deck = Deck {
+            digi_eggs: vec![card("ST1-01", 0, 4)],
+            deck: vec![
+                card("st1-3", 0, 2),
+                card("BT1-010", 0, 1),
+                card("ST1-03", 1, 1),
+                card("ST1-03", 0, 2),
+                card("BT1-010", 0, 2),
+            ],
+            sideboard: vec![],
+            icon: None,
+            language: Some(Language::English),
+            name: "".to_string(),
+        };
+        // lower case card sets are only encoded once canonicalized
+        assert_eq!(
+            try_encode(deck.clone(), 5),
+            Err(EncodeError::InvalidCardNumber {
+                number: "st1-3".to_string()
+            })
+        );
+        assert!(encode_canonical(&deck, 5).is_ok());
+
+        deck.canonicalize();
+        assert_eq!(deck.digi_eggs, vec![card("ST1-01", 0, 4)]);
+        assert_eq!(
+            deck.deck,
+            vec![
+                card("BT1-010", 0, 3),
+                card("ST1-03", 0, 4),
+                card("ST1-03", 1, 1),
+            ]
+        );
+        assert_eq!(
+            encode(deck.clone(), 5),
+            encode_canonical(&deck, 5).unwrap()
+        );
+        assert_eq!(decode(&encode(deck.clone(), 5)), deck);
+
+        // padding doesn't depend on the other cards of the deck
+        let mut wide_deck = deck.clone();
+        wide_deck.deck = vec![card("ST1-01", 0, 1), card("ST1-100", 0, 1)];
+        wide_deck.canonicalize();
+        assert_eq!(wide_deck.deck[0], card("ST1-01", 0, 1));
+        let mut padded_deck = deck.clone();
+        padded_deck.deck = vec![card("ST1-01", 0, 4)];
+        let mut unpadded_deck = deck.clone();
+        unpadded_deck.deck = vec![card("ST1-1", 0, 4)];
+        assert_eq!(
+            encode_canonical(&padded_deck, 5),
+            encode_canonical(&unpadded_deck, 5)
+        );
+
+        // summed counts above 255 are not clamped
+        deck.deck = vec![card("BT6-085", 0, 200), card("BT6-085", 0, 200)];
+        deck.canonicalize();
+        assert_eq!(
+            deck.deck,
+            vec![card("BT6-085", 0, 255), card("BT6-085", 0, 145)]
+        );
+    }
+
+    #[test]
+    fn codec_v7_crc16() {
+        // CRC-16/CCITT-FALSE check value with the checksum bytes skipped
+        assert_eq!(compute_crc16(b"1__23456789"), 0x29B1);
+
+        let (mut deck, checksum_algorithm) =
+            try_decode_verified(ST1_DECK_ENCODED).unwrap();
+        assert_eq!(checksum_algorithm, ChecksumAlgorithm::Additive);
+        deck.language = Some(Language::English);
+
+        // swapping two card records passes the additive checksum of v6 but
+        // not the CRC-16 of v7
+        let swap_card_records = |version: u8| {
+            let mut deck_bytes = Vec::new();
+            encode_bytes_into(&deck, version, &mut deck_bytes).unwrap();
+            let name_start = deck_bytes.len() - deck.name.len();
+            // ST1-14 x4 and ST1-15 x2
+            deck_bytes.swap(name_start - 3, name_start - 2);
+            format!(
+                "DCG{}",
+                base64::Engine::encode(
+                    &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                    deck_bytes
+                )
+            )
+        };
+        let corrupted_v6 = swap_card_records(6);
+        assert!(try_decode(&corrupted_v6).is_ok());
+        assert_ne!(decode(&corrupted_v6).deck, deck.deck);
+
+        let corrupted_v7 = swap_card_records(7);
+        assert!(matches!(
+            try_decode(&corrupted_v7),
+            Err(DecodeError::ChecksumMismatch { offset: 1, .. })
+        ));
+
+        let deck_code = encode(deck.clone(), 7);
+        let (deck_v7, checksum_algorithm) =
+            try_decode_verified(&deck_code).unwrap();
+        assert_eq!(checksum_algorithm, ChecksumAlgorithm::Crc16);
+        assert_eq!(deck_v7.deck, deck.deck);
+        assert_eq!(
+            inspect(&deck_code).unwrap().checksum_algorithm,
+            ChecksumAlgorithm::Crc16
+        );
     }
 }
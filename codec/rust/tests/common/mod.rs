@@ -0,0 +1,17 @@
+//! Fixtures shared by the integration tests
+
+// every test crate compiles its own copy and uses only some of the fixtures
+#![allow(dead_code)]
+
+use dcg_codec::codec::Card;
+
+/// Starter Deck, Gaia Red [ST-1] encoded as v1
+pub const ST1_DECK_ENCODED: &str = "DCGETsdnJ0BQQMBnJ0BTwMCAwEDAQMBAwEBAQMBAwEBAQEBAwEDAQMBAQEBAVN0YXJ0ZXIgRGVjaywgR2FpYSBSZWQgW1NULTFd";
+
+pub fn card(number: &str, parallel_id: u8, count: u8) -> Card {
+    Card {
+        number: number.to_string(),
+        parallel_id,
+        count,
+    }
+}
@@ -0,0 +1,79 @@
+use dcg_codec::codec::{decode, Language};
+use dcg_codec::diff::{Change, DeckDiff, SectionDiff};
+
+mod common;
+use common::card;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deck_diff() {
+        // v3
+        let deck_with_sideboard_and_language_encoded = "DCGOkA_B4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCV9fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
+        let deck = decode(deck_with_sideboard_and_language_encoded);
+        assert!(deck.diff(&deck).is_empty());
+
+        let mut updated_deck = deck.clone();
+        updated_deck.digi_eggs.push(card("ST1-01", 1, 1));
+        // duplicate entries and padding are not changes
+        updated_deck.deck.push(card("BT1-9", 0, 1));
+        updated_deck.deck.retain(|c| c.number != "BT1-009");
+        updated_deck
+            .deck
+            .iter_mut()
+            .find(|c| c.number == "BT1-019")
+            .unwrap()
+            .count -= 1;
+        updated_deck.sideboard.clear();
+        updated_deck.language = Some(Language::Japanese);
+        updated_deck.name = "Red".to_string();
+
+        let deck_diff = deck.diff(&updated_deck);
+        assert_eq!(
+            deck_diff,
+            DeckDiff {
+                digi_eggs: SectionDiff {
+                    added: vec![card("ST1-01", 1, 1)],
+                    removed: vec![],
+                },
+                deck: SectionDiff {
+                    added: vec![],
+                    removed: vec![card("BT1-019", 0, 1)],
+                },
+                sideboard: SectionDiff {
+                    added: vec![],
+                    removed: deck.sideboard.clone(),
+                },
+                name: Some(Change {
+                    from: deck.name.clone(),
+                    to: "Red".to_string()
+                }),
+                icon: None,
+                language: Some(Change {
+                    from: Some(Language::English),
+                    to: Some(Language::Japanese)
+                }),
+            }
+        );
+        assert!(deck_diff.to_string().ends_with("Language: en -> ja\n"));
+        assert_eq!(updated_deck.diff(&deck).digi_eggs.removed.len(), 1);
+
+        // padding doesn't depend on the other cards of the deck
+        let mut wide_deck = deck.clone();
+        wide_deck.deck = vec![card("ST1-01", 0, 4), card("ST1-100", 0, 1)];
+        let mut unpadded_deck = wide_deck.clone();
+        unpadded_deck.deck[0].number = "ST1-1".to_string();
+        assert!(wide_deck.diff(&unpadded_deck).is_empty());
+
+        // summed counts above 255 are not clamped
+        let mut many_deck = wide_deck.clone();
+        many_deck.deck.push(card("BT6-085", 0, 200));
+        many_deck.deck.push(card("BT6-085", 0, 200));
+        assert_eq!(
+            wide_deck.diff(&many_deck).deck.added,
+            vec![card("BT6-085", 0, 255), card("BT6-085", 0, 145)]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use dcg_codec::codec::{decode, Card, Language};
+use dcg_codec::limitation::{
+    Date, LimitationList, LoadLimitationsError, ParseDateError,
+};
+use dcg_codec::validate::Violation;
+
+mod common;
+use common::ST1_DECK_ENCODED;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITATIONS_JSON: &str = r#"{
+        "ST1-07": {
+            "en": [
+                {"id": "limitation/en_ST1-07_0", "type": "restrict",
+                 "date": "2021-03-01", "allowance": 1, "note": ""},
+                {"id": "limitation/en_ST1-07_1", "type": "unrestrict",
+                 "date": "2022-03-01", "note": ""}
+            ],
+            "default": [
+                {"id": "limitation/ST1-07_0", "type": "restrict",
+                 "date": "2021-03-01", "allowance": 1},
+                {"id": "limitation/ST1-07_1", "type": "unrestrict",
+                 "date": "2022-03-01"}
+            ],
+            "ja": [
+                {"id": "limitation/ja_ST1-07_0", "type": "ban",
+                 "date": "2021-01-01", "allowance": 0, "note": ""}
+            ]
+        },
+        "ST1-13": {
+            "default": [
+                {"id": "limitation/ST1-13_0", "type": "banned-pair",
+                 "date": "2022-06-01", "allowance": 0,
+                 "paired-card-numbers": ["ST1-3", "BT1-001"]}
+            ]
+        }
+    }"#;
+
+    const LIMITATIONS_EDN: &str = r#"{"ST1-07" {:default [{:limitation/id "limitation/ST1-07_0"
+                                    :limitation/type :restrict
+                                    :limitation/date #inst "2021-03-01T00:00:00.000-00:00"
+                                    :limitation/allowance 1}
+                                   {:limitation/id "limitation/ST1-07_1"
+                                    :limitation/type :unrestrict
+                                    :limitation/date #inst "2022-03-01T00:00:00.000-00:00"}]}
+ "ST1-13" {:default [{:limitation/id "limitation/ST1-13_0"
+                      :limitation/type :banned-pair
+                      :limitation/date #inst "2022-06-01T00:00:00.000-00:00"
+                      :limitation/allowance 0
+                      :limitation/paired-card-numbers ["ST1-3" "BT1-001"]}]}}"#;
+
+    fn date(date: &str) -> Date {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            date("2022-05-13"),
+            Date {
+                year: 2022,
+                month: 5,
+                day: 13
+            }
+        );
+        assert_eq!(date("2022-05-13T04:00:00.000-00:00"), date("2022-05-13"));
+        assert_eq!(date("2022-05-13").to_string(), "2022-05-13");
+        assert!(date("2021-12-31") < date("2022-01-01"));
+        assert_eq!(date("2024-02-29").day, 29);
+        assert_eq!(date("2000-02-29").day, 29);
+        for invalid_date in [
+            "2022-5-13",
+            "2022-13-01",
+            "2022-05-00",
+            "May",
+            "2022-02-31",
+            "2022-04-31",
+            "2023-02-29",
+            "1900-02-29",
+        ] {
+            assert_eq!(
+                invalid_date.parse::<Date>(),
+                Err(ParseDateError {
+                    date: invalid_date.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn limitation_list_check() {
+        let deck = decode(ST1_DECK_ENCODED);
+        let limitation_list =
+            LimitationList::from_json(LIMITATIONS_JSON).unwrap();
+        assert_eq!(
+            LimitationList::from_edn(LIMITATIONS_EDN)
+                .unwrap()
+                .check(&deck, &date("2021-06-01")),
+            limitation_list.check(&deck, &date("2021-06-01"))
+        );
+
+        // before any limitation
+        assert_eq!(limitation_list.check(&deck, &date("2021-02-28")), vec![]);
+        // restricted from its effective date
+        let restricted = Violation::Restricted {
+            number: "ST1-07".to_string(),
+            count: 2,
+            max: 1,
+        };
+        assert_eq!(
+            limitation_list.check(&deck, &date("2021-03-01")),
+            vec![restricted.clone()]
+        );
+        assert_eq!(
+            restricted.to_string(),
+            "ST1-07 is restricted to 1 but has 2 copies"
+        );
+        // lifted, then paired with ST1-03 which is in the deck
+        assert_eq!(limitation_list.check(&deck, &date("2022-03-01")), vec![]);
+        assert_eq!(
+            limitation_list.check(&deck, &date("2022-06-01")),
+            vec![Violation::BannedPair {
+                number: "ST1-03".to_string(),
+                paired_number: "ST1-13".to_string(),
+            }]
+        );
+
+        // limitations of the deck language, with parallel ids and padding
+        // counted as one card number
+        let mut ja_deck = deck.clone();
+        ja_deck.language = Some(Language::Japanese);
+        ja_deck.sideboard.push(Card {
+            number: "ST1-7".to_string(),
+            parallel_id: 1,
+            count: 1,
+        });
+        assert_eq!(
+            limitation_list.check(&ja_deck, &date("2021-01-01")),
+            vec![Violation::Banned {
+                number: "ST1-07".to_string(),
+                count: 3,
+            }]
+        );
+
+        assert!(matches!(
+            LimitationList::from_json("[]"),
+            Err(LoadLimitationsError::Parse(_))
+        ));
+        assert!(matches!(
+            LimitationList::from_edn(
+                r#"{"ST1-07" {:default [{:limitation/type :retire}]}}"#
+            ),
+            Err(LoadLimitationsError::Parse(_))
+        ));
+        assert!(matches!(
+            LimitationList::load("limitations-that-do-not-exist.json"),
+            Err(LoadLimitationsError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+use dcg_codec::codec::{decode, encode, Deck, Language};
+use dcg_codec::normalize::PaddingRules;
+
+mod common;
+use common::card;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_card_numbers() {
+        let padding_rules = PaddingRules::default();
+        for (typed_number, number) in [
+            ("bt1-1", "BT1-001"),
+            ("BT01-001", "BT1-001"),
+            ("st1_01", "ST1-01"),
+            ("ST1-001", "ST1-01"),
+            ("st10 1", "ST10-01"),
+            ("p-1", "P-001"),
+            (" ex2-71 ", "EX2-071"),
+            // numbers too wide for the rule keep their digits
+            ("ST1-100", "ST1-100"),
+            // card sets without a rule keep the typed padding
+            ("XY1-1", "XY1-1"),
+        ] {
+            assert_eq!(
+                padding_rules.normalize(typed_number).unwrap().to_string(),
+                number
+            );
+        }
+        assert!(padding_rules.normalize("BT1").is_err());
+
+        let mut padding_rules = PaddingRules::new();
+        assert_eq!(
+            padding_rules.normalize("bt1-1").unwrap().to_string(),
+            "BT1-1"
+        );
+        padding_rules.set_padding("bt", 4);
+        assert_eq!(padding_rules.padding("BT12"), Some(4));
+        assert_eq!(
+            padding_rules.normalize("bt1-1").unwrap().to_string(),
+            "BT1-0001"
+        );
+        let padding_rules: PaddingRules =
+            serde_json::from_str(r#"{"st": 3}"#).unwrap();
+        assert_eq!(
+            padding_rules.normalize("st1_01").unwrap().to_string(),
+            "ST1-001"
+        );
+    }
+
+    #[test]
+    fn normalize_deck() {
+        let mut deck = Deck {
+            digi_eggs: vec![card("st1_1", 0, 4)],
+            deck: vec![
+                card("bt1-85", 0, 2),
+                card("BT01-085", 0, 2),
+                card("ST1-003", 0, 4),
+            ],
+            sideboard: vec![card("not a card", 0, 1)],
+            icon: None,
+            language: Some(Language::English),
+            name: "".to_string(),
+        };
+        deck.normalize(&PaddingRules::default());
+        assert_eq!(deck.digi_eggs, vec![card("ST1-01", 0, 4)]);
+        assert_eq!(
+            deck.deck,
+            vec![card("BT1-085", 0, 2), card("BT1-085", 0, 2), card("ST1-03", 0, 4)]
+        );
+        assert_eq!(deck.sideboard, vec![card("not a card", 0, 1)]);
+
+        deck.sideboard.clear();
+        deck.canonicalize();
+        assert_eq!(deck.deck[0], card("BT1-085", 0, 4));
+        assert_eq!(decode(&encode(deck.clone(), 7)), deck);
+    }
+}
@@ -0,0 +1,85 @@
+use dcg_codec::codec::{decode, Card};
+use dcg_codec::probability::{at_least, cards_seen, exactly, TurnOrder};
+
+mod common;
+use common::ST1_DECK_ENCODED;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn hypergeometric() {
+        // 1 - C(46,5) / C(50,5)
+        assert_close(at_least(50, 4, 5, 1), 0.35303951367781156);
+        // C(4,2) * C(46,3) / C(50,5)
+        assert_close(exactly(50, 4, 5, 2), 0.04298740772904907);
+        assert_close(at_least(50, 4, 5, 4), 2.1710811984368215e-05);
+        assert_close(at_least(50, 12, 5, 2), 0.3450310559006211);
+        assert_close((0..=4).map(|count| exactly(50, 4, 5, count)).sum(), 1.0);
+        assert_eq!(at_least(50, 4, 5, 0), 1.0);
+        assert_eq!(at_least(50, 4, 5, 5), 0.0);
+        assert_eq!(at_least(50, 0, 5, 1), 0.0);
+        assert_eq!(exactly(50, 4, 5, 6), 0.0);
+        // every card drawn
+        assert_eq!(at_least(50, 4, 60, 4), 1.0);
+
+        // C(130,65) fits in a u128 but C(132,66) does not
+        assert_close(at_least(130, 4, 65, 1), 0.9403955319538546);
+        assert_close(at_least(132, 4, 66, 1), 0.9403515000887627);
+        let close = |a: f64, b: f64| assert!((a - b).abs() < 1e-9);
+        close(exactly(200, 100, 100, 50), 0.11241557570404212);
+        close(at_least(200, 100, 100, 50), 0.5562077878520211);
+        close(at_least(200, 100, 100, 60), 0.0035297577475081232);
+        close(
+            (0..=100).map(|count| exactly(200, 100, 100, count)).sum(),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn deck_probabilities() {
+        assert_eq!(cards_seen(1, TurnOrder::First), 5);
+        assert_eq!(cards_seen(1, TurnOrder::Second), 6);
+        assert_eq!(cards_seen(3, TurnOrder::First), 7);
+        assert_eq!(cards_seen(3, TurnOrder::Second), 8);
+
+        let mut deck = decode(ST1_DECK_ENCODED);
+        assert_eq!(deck.main_deck_size(), 50);
+        // digi-eggs are not in the main deck
+        assert_eq!(deck.group_count(&["ST1-01"]), 0);
+        assert_eq!(deck.group_count(&["st1-3"]), 4);
+        assert_eq!(deck.group_count(&["ST1-03", "ST1-07", "ST1-99"]), 6);
+
+        assert_close(
+            deck.opening_hand_probability(&["ST1-03"], 1),
+            0.35303951367781156,
+        );
+        assert_close(
+            deck.by_turn_probability(&["ST1-03"], 1, 3, TurnOrder::First),
+            0.4641337386018237,
+        );
+        assert_close(
+            deck.by_turn_probability(&["ST1-03"], 1, 3, TurnOrder::Second),
+            0.5139817629179332,
+        );
+        assert_close(
+            deck.security_probability(&["ST1-03"], 1),
+            deck.opening_hand_probability(&["ST1-03"], 1),
+        );
+        assert_eq!(deck.opening_hand_probability(&["ST1-01"], 1), 0.0);
+
+        // alternate arts count as copies of the card number
+        deck.deck[1].count = 2;
+        deck.deck.push(Card {
+            number: "ST1-03".to_string(),
+            parallel_id: 1,
+            count: 2,
+        });
+        assert_eq!(deck.group_count(&["ST1-03"]), 4);
+    }
+}
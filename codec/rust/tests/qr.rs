@@ -0,0 +1,212 @@
+use dcg_codec::codec::decode;
+use dcg_codec::qr::{
+    render_png, render_svg, scan, EcLevel, QrError, QrOptions, ScanQrError,
+    MAX_MODULE_SIZE,
+};
+
+mod common;
+use common::ST1_DECK_ENCODED;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Width and height of a PNG image
+    fn png_size(png_bytes: &[u8]) -> (u32, u32) {
+        let reader = png::Decoder::new(std::io::Cursor::new(png_bytes))
+            .read_info()
+            .unwrap();
+        let info = reader.info();
+        (info.width, info.height)
+    }
+
+    /// Width, height and pixels of a grayscale PNG image
+    fn png_pixels(png_bytes: &[u8]) -> (u16, u16, Vec<u8>) {
+        let mut reader = png::Decoder::new(std::io::Cursor::new(png_bytes))
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        (info.width as u16, info.height as u16, pixels)
+    }
+
+    #[test]
+    fn qr_svg() {
+        let svg = render_svg(ST1_DECK_ENCODED, &QrOptions::default()).unwrap();
+        // version 6 QR code of 41 modules with a quiet zone of 4 modules
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"392\" height=\"392\" viewBox=\"0 0 49 49\""
+        ));
+        // top left finder pattern
+        assert!(svg.contains("<path d=\"M4,4h7v1h-7z"));
+        assert!(!svg.contains("<text"));
+
+        let svg = render_svg(
+            ST1_DECK_ENCODED,
+            &QrOptions {
+                ec_level: EcLevel::High,
+                module_size: 2,
+                caption: Some("Gaia Red <ST-1> & co".to_string()),
+            },
+        )
+        .unwrap();
+        assert!(svg.contains("viewBox=\"0 0 65 69\""));
+        assert!(svg.contains(">Gaia Red &lt;ST-1&gt; &amp; co</text>"));
+
+        // long captions widen the image on both sides of the QR code
+        let svg = render_svg(
+            ST1_DECK_ENCODED,
+            &QrOptions {
+                module_size: 2,
+                caption: Some("_".repeat(40)),
+                ..QrOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"126\" height=\"106\" viewBox=\"-7 0 63 53\""
+        ));
+        assert!(svg.contains("<rect x=\"-7\" width=\"63\" height=\"53\""));
+    }
+
+    #[test]
+    fn qr_png() {
+        let options = QrOptions {
+            module_size: 4,
+            ..QrOptions::default()
+        };
+        let png_bytes = render_png(ST1_DECK_ENCODED, &options).unwrap();
+        assert_eq!(png_size(&png_bytes), (49 * 4, 49 * 4));
+
+        // caption is 8 pixels high at half the module size, with a module
+        // of space below
+        let png_bytes = render_png(
+            ST1_DECK_ENCODED,
+            &QrOptions {
+                caption: Some("がいあ Red".to_string()),
+                ..options.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(png_size(&png_bytes), (49 * 4, 49 * 4 + 8 * 2 + 4));
+
+        // wide captions widen the image
+        let png_bytes = render_png(
+            ST1_DECK_ENCODED,
+            &QrOptions {
+                caption: Some("_".repeat(20)),
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(png_size(&png_bytes).0, 20 * 8 * 2 + 2 * 4);
+    }
+
+    #[test]
+    fn qr_errors() {
+        assert_eq!("q".parse(), Ok(EcLevel::Quartile));
+        assert!("X".parse::<EcLevel>().is_err());
+        let options = QrOptions {
+            ec_level: EcLevel::High,
+            ..QrOptions::default()
+        };
+        assert_eq!(
+            render_svg(&"A".repeat(3000), &options),
+            Err(QrError::DataTooLong)
+        );
+        assert_eq!(
+            render_png(&"A".repeat(3000), &options),
+            Err(QrError::DataTooLong)
+        );
+        // katakana and kanji aren't in the PNG caption font
+        let options = QrOptions {
+            caption: Some("デジモン".to_string()),
+            ..QrOptions::default()
+        };
+        assert_eq!(
+            render_png(ST1_DECK_ENCODED, &options),
+            Err(QrError::CaptionCharacter('デ'))
+        );
+        assert!(render_svg(ST1_DECK_ENCODED, &options).is_ok());
+        let options = QrOptions {
+            module_size: MAX_MODULE_SIZE + 1,
+            ..QrOptions::default()
+        };
+        assert_eq!(
+            render_svg(ST1_DECK_ENCODED, &options),
+            Err(QrError::ModuleSizeTooLarge(MAX_MODULE_SIZE + 1))
+        );
+        assert_eq!(
+            render_png(ST1_DECK_ENCODED, &options),
+            Err(QrError::ModuleSizeTooLarge(MAX_MODULE_SIZE + 1))
+        );
+        assert!(render_png(
+            ST1_DECK_ENCODED,
+            &QrOptions {
+                module_size: MAX_MODULE_SIZE,
+                ..QrOptions::default()
+            }
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn qr_scan() {
+        let deck_codes = [
+            // v0
+            "DCGApQzQlQyIIHBU1QxIEEBQlQxIIQFAsYCQU0QQlQyIIHEBEJUMyCGxALFAYNCwYUNU1QxIEbCwYMBiEUCRGlnaSBCcm9zOiBSYWduYWxvYXJkbW9uIFJlZCAoeW91dHUuYmUvbzBLb1cyd3doUjQp",
+            // v1
+            ST1_DECK_ENCODED,
+            // v2
+            "DCGIkA_B4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCV9fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw",
+            // v3
+            "DCGOkA_B4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCV9fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw",
+            // v4
+            "DCGQsA_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw",
+            // v5
+            "DCGUsC_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw",
+        ];
+        for deck_code in deck_codes {
+            let options = QrOptions {
+                module_size: 4,
+                caption: Some(decode(deck_code).name),
+                ..QrOptions::default()
+            };
+            let png_bytes = render_png(deck_code, &options).unwrap();
+            assert_eq!(scan(&png_bytes).as_deref(), Ok(deck_code));
+
+            let (width, height, pixels) = png_pixels(&png_bytes);
+            let mut jpeg_bytes = Vec::new();
+            jpeg_encoder::Encoder::new(&mut jpeg_bytes, 80)
+                .encode(&pixels, width, height, jpeg_encoder::ColorType::Luma)
+                .unwrap();
+            assert_eq!(scan(&jpeg_bytes).as_deref(), Ok(deck_code));
+        }
+    }
+
+    #[test]
+    fn qr_scan_errors() {
+        assert_eq!(scan(b"DCG"), Err(ScanQrError::Image));
+        assert_eq!(scan(b"\x89PNG\r\n\x1a\n"), Err(ScanQrError::Image));
+        let blank_png_bytes = {
+            let mut png_bytes = Vec::new();
+            let mut encoder = png::Encoder::new(&mut png_bytes, 64, 64);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder
+                .write_header()
+                .unwrap()
+                .write_image_data(&[0xFF; 64 * 64])
+                .unwrap();
+            png_bytes
+        };
+        assert_eq!(scan(&blank_png_bytes), Err(ScanQrError::NotFound));
+        let url_png_bytes =
+            render_png("https://example.com", &QrOptions::default()).unwrap();
+        assert_eq!(
+            scan(&url_png_bytes),
+            Err(ScanQrError::NotDeckCode {
+                payload: "https://example.com".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use dcg_codec::carddb::CardDb;
+use dcg_codec::codec::Deck;
+use dcg_codec::stats::DeckStats;
+use std::collections::BTreeMap;
+
+mod common;
+use common::card;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BULK_DATA_JSON: &str = r#"[
+{"data":{"type":"card","id":"/cards/en/ST1-01","attributes":{"name":"Koromon","number":"ST1-01","category":"digi-egg","parallel-id":0,"level":2,"language":"en","form":"In-Training","type":"Lesser","rarity":"U","color":["red"]}}},
+{"data":{"type":"card","id":"/cards/en/ST1-03","attributes":{"name":"Agumon","number":"ST1-03","category":"digimon","parallel-id":0,"level":3,"dp":2000,"play-cost":3,"language":"en","form":"Rookie","attribute":"Vaccine","type":"Reptile","rarity":"U","color":["red"],"digivolution-requirements":[{"level":2,"category":"digimon","color":["red"],"cost":0}]}}},
+{"data":{"type":"card","id":"/cards/en/BT1-085","attributes":{"name":"Dorumon","number":"BT1-085","category":"digimon","parallel-id":0,"level":3,"play-cost":3,"language":"en","form":"Rookie","attribute":"Data","type":"Mini Dragon/Dragon Kin","rarity":"R","color":["red","purple"],"digivolution-requirements":[{"level":2,"category":"digimon","color":["purple"],"cost":1},{"level":2,"category":"digimon","color":["red"],"cost":0}],"security-effect":"[Security] Play this card without paying its memory cost."}}},
+{"data":{"type":"card","id":"/cards/en/ST1-12","attributes":{"name":"Tai Kamiya","number":"ST1-12","category":"tamer","parallel-id":0,"play-cost":2,"language":"en","rarity":"R","color":["red"],"security-effect":"[Security] Play this card without paying its memory cost."}}},
+{"data":{"type":"card","id":"/cards/en/ST1-16","attributes":{"name":"Gaia Force","number":"ST1-16","category":"option","parallel-id":0,"use-cost":6,"language":"en","rarity":"C","color":["red"],"security-effect":""}}}
+]"#;
+
+    fn counts<K: Ord + Clone>(counts: &[(K, u32)]) -> BTreeMap<K, u32> {
+        counts.iter().cloned().collect()
+    }
+
+    fn strings(values: &[(&str, u32)]) -> BTreeMap<String, u32> {
+        values
+            .iter()
+            .map(|(key, count)| (key.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn deck_stats() {
+        let card_db = CardDb::from_json(BULK_DATA_JSON).unwrap();
+        let deck = Deck {
+            digi_eggs: vec![card("ST1-01", 0, 4)],
+            deck: vec![
+                card("ST1-03", 0, 4),
+                card("BT1-85", 0, 2),
+                // alternate art without card data uses the original art
+                card("BT1-85", 1, 1),
+                card("ST1-12", 0, 3),
+                card("ST1-16", 0, 2),
+                card("ST1-99", 0, 1),
+            ],
+            // left out
+            sideboard: vec![card("ST1-03", 0, 4)],
+            icon: None,
+            language: None,
+            name: String::new(),
+        };
+        let stats = DeckStats::compute(&deck, &card_db);
+        assert_eq!(
+            stats,
+            DeckStats {
+                cards: 17,
+                unknown_cards: vec![card("ST1-99", 0, 1)],
+                categories: strings(&[
+                    ("digi-egg", 4),
+                    ("digimon", 7),
+                    ("option", 2),
+                    ("tamer", 3)
+                ]),
+                colors: strings(&[("purple", 3), ("red", 16)]),
+                levels: counts(&[(2, 4), (3, 7)]),
+                play_costs: counts(&[(2, 3), (3, 7), (6, 2)]),
+                digivolve_costs: counts(&[(0, 7)]),
+                traits: strings(&[
+                    ("Data", 3),
+                    ("Dragon Kin", 3),
+                    ("In-Training", 4),
+                    ("Lesser", 4),
+                    ("Mini Dragon", 3),
+                    ("Reptile", 4),
+                    ("Rookie", 7),
+                    ("Vaccine", 4),
+                ]),
+                security_effects: 6,
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "Cards: 17
+Not in card database
+  1 ST1-99
+Categories
+  digi-egg: 4
+  digimon: 7
+  option: 2
+  tamer: 3
+Colors
+  purple: 3
+  red: 16
+Levels
+  Lv.2: 4
+  Lv.3: 7
+Play costs
+  2: 3
+  3: 7
+  6: 2
+Digivolve costs
+  0: 7
+Traits
+  Data: 3
+  Dragon Kin: 3
+  In-Training: 4
+  Lesser: 4
+  Mini Dragon: 3
+  Reptile: 4
+  Rookie: 7
+  Vaccine: 4
+Security effects: 6
+"
+        );
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.starts_with(
+            r#"{"cards":17,"unknown-cards":[{"number":"ST1-99","count":1}],"categories":{"digi-egg":4,"#
+        ));
+        assert!(json.contains(r#""levels":{"2":4,"3":7}"#));
+        assert!(json.ends_with(r#""security-effects":6}"#));
+
+        assert_eq!(
+            DeckStats::compute(&deck, &CardDb::default())
+                .unknown_cards
+                .len(),
+            7
+        );
+    }
+}
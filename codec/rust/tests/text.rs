@@ -0,0 +1,98 @@
+use dcg_codec::codec::{decode, Deck, Language};
+use dcg_codec::text::ParseDeckTextError;
+
+mod common;
+use common::card;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deck_from_text() {
+        let deck = Deck::from_text(
+            "# Gaia Red, pasted from the forum
+Name: Starter Deck, Gaia Red [ST-1]
+Language: en
+
+Digi-Egg (4)
+4x ST1-01 Koromon
+
+Main Deck:
+4 ST1-02 Agumon
+x4 ST1-03
+2 x BT1-085 (P1) Agumon
+// tech
+1 BT1-009 Monzaemon: Red
+
+Sideboard
+1X st1_16 Gaiamon
+",
+        )
+        .unwrap();
+        assert_eq!(
+            deck,
+            Deck {
+                digi_eggs: vec![card("ST1-01", 0, 4)],
+                deck: vec![
+                    card("ST1-02", 0, 4),
+                    card("ST1-03", 0, 4),
+                    card("BT1-085", 1, 2),
+                    card("BT1-009", 0, 1),
+                ],
+                sideboard: vec![card("ST1-16", 0, 1)],
+                icon: None,
+                language: Some(Language::English),
+                name: "Starter Deck, Gaia Red [ST-1]".to_string(),
+            }
+        );
+
+        // cards without a header are in the main deck
+        assert_eq!(
+            Deck::from_text("4 ST1-03").unwrap().deck,
+            vec![card("ST1-03", 0, 4)]
+        );
+
+        for (text, line) in [
+            ("Main\n4 Agumon", 2),
+            ("0 ST1-03", 1),
+            ("4", 1),
+            ("\nLanguage: fr", 2),
+        ] {
+            assert_eq!(
+                Deck::from_text(text),
+                Err(ParseDeckTextError {
+                    line,
+                    text: text.lines().nth(line - 1).unwrap().to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn deck_to_text() {
+        // v5
+        let deck_with_sideboard_and_language_zh_and_icon_encoded = "DCGUsC_h4udAoEDAZydAUEAAYudAYQACQMKAQEBMQSLnQKBAxABi50DhQMIAwUCAwECAwGLnQOBAhgEnJ0BRgMCAwECAwABAiABCUJBQ0stMDAxX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fX19fXw";
+        let deck = decode(deck_with_sideboard_and_language_zh_and_icon_encoded);
+        let text = deck.to_text();
+        assert!(text.starts_with(&format!(
+            "Name: {}\n\
+             Icon: BACK-001\n\
+             Language: zh-Hans\n\
+             \n\
+             Digi-Egg\n\
+             4 BT2-001\n",
+            "_".repeat(55)
+        )));
+        assert!(text.contains("\nMain\n"));
+        assert!(text.contains("\nSideboard\n"));
+        assert!(text.contains("\n2 BT1-085 (P1)\n"));
+        assert_eq!(Deck::from_text(&text).unwrap(), deck);
+        assert_eq!(Deck::from_text(&text).unwrap().to_text(), text);
+
+        let mut deck = Deck::from_text("4 ST1-03").unwrap();
+        assert_eq!(deck.to_text(), "Main\n4 ST1-03\n");
+        deck.deck.clear();
+        assert_eq!(deck.to_text(), "");
+    }
+}
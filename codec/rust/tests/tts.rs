@@ -0,0 +1,82 @@
+use dcg_codec::codec::{Deck, Language};
+use dcg_codec::tts::TtsOptions;
+
+mod common;
+use common::card;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(face_url_template: &str) -> TtsOptions {
+        TtsOptions {
+            face_url_template: face_url_template.to_string(),
+            back_url: "https://images.example.com/back.webp".to_string(),
+            language: Language::English,
+        }
+    }
+
+    #[test]
+    fn tts_face_urls() {
+        let options = options(
+            "https://images.example.com/{language}/{id}/{image}/{number}/{parallel_id}",
+        );
+        assert_eq!(
+            options.face_url(&Language::English, &card("BT5-012", 1, 1)),
+            "https://images.example.com/en/en_BT5-012_P1/BT5-012_P1/BT5-012/1"
+        );
+        assert_eq!(
+            options.face_url(&Language::Chinese, &card("BT5-012", 0, 1)),
+            "https://images.example.com/zh-Hans/zh-Hans_BT5-012_P0/BT5-012/BT5-012/0"
+        );
+    }
+
+    #[test]
+    fn deck_to_tts() {
+        let mut deck = Deck {
+            digi_eggs: vec![card("ST1-01", 0, 4)],
+            deck: vec![card("ST1-03", 0, 2), card("BT1-085", 1, 1)],
+            sideboard: vec![card("ST1-16", 0, 1)],
+            icon: None,
+            language: Some(Language::Japanese),
+            name: "Gaia Red".to_string(),
+        };
+        let tts_save = deck.to_tts(&options("{id}"));
+        assert_eq!(tts_save.save_name, "Gaia Red");
+        let nicknames: Vec<&str> = tts_save
+            .object_states
+            .iter()
+            .map(|object| object.nickname.as_str())
+            .collect();
+        assert_eq!(nicknames, ["Digi-Egg", "Main", "ST1-16"]);
+
+        let main = &tts_save.object_states[1];
+        assert_eq!(main.name, "DeckCustom");
+        assert_eq!(main.deck_ids, [200, 200, 300]);
+        assert_eq!(main.contained_objects.len(), 3);
+        assert_eq!(main.contained_objects[2].card_id, Some(300));
+        assert_eq!(main.custom_deck[&3].face_url, "ja_BT1-085_P1");
+
+        // a single card is exported as a card object
+        let sideboard = &tts_save.object_states[2];
+        assert_eq!(sideboard.name, "Card");
+        assert_eq!(sideboard.card_id, Some(400));
+        assert_eq!(sideboard.custom_deck[&4].face_url, "ja_ST1-16_P0");
+
+        let json = serde_json::to_value(&tts_save).unwrap();
+        assert_eq!(json["ObjectStates"][1]["DeckIDs"][2], 300);
+        assert_eq!(
+            json["ObjectStates"][0]["CustomDeck"]["1"]["BackURL"],
+            "https://images.example.com/back.webp"
+        );
+        assert_eq!(json["ObjectStates"][0]["Transform"]["rotZ"], 180.0);
+        assert!(json["ObjectStates"][2].get("DeckIDs").is_none());
+
+        // decks without a language use the option language
+        deck.language = None;
+        deck.sideboard.clear();
+        let tts_save = deck.to_tts(&options("{language}"));
+        assert_eq!(tts_save.object_states.len(), 2);
+        assert_eq!(tts_save.object_states[0].custom_deck[&1].face_url, "en");
+    }
+}
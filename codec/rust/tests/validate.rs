@@ -0,0 +1,97 @@
+use dcg_codec::codec::decode;
+use dcg_codec::validate::{RuleSet, Violation};
+
+mod common;
+use common::{card, ST1_DECK_ENCODED};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_deck() {
+        let st1_deck = decode(ST1_DECK_ENCODED);
+        let rule_set = RuleSet::default();
+        assert_eq!(st1_deck.validate(&rule_set), vec![]);
+
+        let mut deck = st1_deck.clone();
+        deck.digi_eggs.push(card("ST1-01", 1, 2));
+        deck.deck.truncate(14);
+        deck.sideboard = vec![card("BT1-009", 0, 3), card("BT1-9", 1, 8)];
+        assert_eq!(
+            deck.validate(&rule_set),
+            vec![
+                Violation::DeckSize {
+                    count: 48,
+                    expected: 50
+                },
+                Violation::DigiEggCount {
+                    count: 6,
+                    min: 0,
+                    max: 5
+                },
+                Violation::SideboardSize { count: 11, max: 10 },
+                // padding and parallel ids are the same card number
+                Violation::TooManyCopies {
+                    number: "BT1-009".to_string(),
+                    count: 11,
+                    max: 4
+                },
+                Violation::TooManyCopies {
+                    number: "ST1-01".to_string(),
+                    count: 6,
+                    max: 4
+                },
+            ]
+        );
+        assert_eq!(
+            deck.validate(&rule_set)[4].to_string(),
+            "ST1-01 has 6 copies, more than 4"
+        );
+
+        // BT6-085 allows up to 50 copies
+        let mut deck = st1_deck.clone();
+        deck.deck = vec![card("BT6-085", 0, 46), card("bt6-85", 1, 4)];
+        assert_eq!(deck.validate(&rule_set), vec![]);
+        deck.deck[0].count = 47;
+        deck.deck[1].count = 4;
+        assert_eq!(
+            deck.validate(&rule_set),
+            vec![
+                Violation::DeckSize {
+                    count: 51,
+                    expected: 50
+                },
+                Violation::TooManyCopies {
+                    number: "BT6-085".to_string(),
+                    count: 51,
+                    max: 50
+                },
+            ]
+        );
+
+        let mut rule_set = RuleSet::default();
+        rule_set.min_digi_eggs = 4;
+        rule_set.max_copies = 3;
+        rule_set.set_copy_limit("ST1-1", 4);
+        assert_eq!(rule_set.copy_limit("st1-01"), 4);
+        let mut deck = st1_deck;
+        deck.digi_eggs.clear();
+        assert_eq!(
+            deck.validate(&rule_set)[0],
+            Violation::DigiEggCount {
+                count: 0,
+                min: 4,
+                max: 5
+            }
+        );
+        assert_eq!(
+            deck.validate(&rule_set)[1],
+            Violation::TooManyCopies {
+                number: "ST1-02".to_string(),
+                count: 4,
+                max: 3
+            }
+        );
+    }
+}